@@ -0,0 +1,346 @@
+use super::fs::format_permissions;
+use super::paths;
+use super::template::Template;
+use super::{Error, Node};
+use flate2::read::GzDecoder;
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+pub enum ArchiveFormat {
+    Tar,
+    Zip,
+}
+
+struct Entry {
+    path: String,
+    size: u64,
+    mode: Option<u32>,
+    kind: &'static str,
+}
+
+pub fn deserialize(
+    serialized: String,
+    archive_format: ArchiveFormat,
+    template_str: String,
+    default: Option<String>,
+    root_name: Option<String>,
+) -> Result<Node, Error> {
+    let archive_path = serialized.trim();
+    if archive_path.is_empty() {
+        return Err(Error::EmptyInputError);
+    }
+    let bytes = std::fs::read(archive_path).map_err(|io_error| {
+        Error::FormatSpecificError(format!("cannot read `{}`: {}", archive_path, io_error))
+    })?;
+    // Archives with several top-level entries go under the archive itself
+    let root_name = root_name.unwrap_or_else(|| {
+        Path::new(archive_path).file_name().map_or_else(
+            || archive_path.to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        )
+    });
+    archive_to_node(&bytes, archive_format, template_str, default, root_name)
+}
+
+fn archive_to_node(
+    bytes: &[u8],
+    archive_format: ArchiveFormat,
+    template_str: String,
+    default: Option<String>,
+    root_name: String,
+) -> Result<Node, Error> {
+    let template = Template::new(template_str)?;
+    let entries = match archive_format {
+        ArchiveFormat::Tar => tar_entries(bytes)?,
+        ArchiveFormat::Zip => zip_entries(bytes)?,
+    };
+    let mut top_level = Vec::new();
+    let mut entries_by_path = HashMap::new();
+    for entry in entries {
+        paths::insert_path(&mut top_level, &entry.path);
+        entries_by_path.insert(paths::components(&entry.path).join("/"), entry);
+    }
+    // The nodes keep the names of their path components until the whole tree
+    // is built, since later entries have to be able to find their parents
+    fill_names(&mut top_level, "", &entries_by_path, &template, &default)?;
+    if top_level.len() == 1 {
+        Ok(top_level.pop().unwrap())
+    } else if top_level.is_empty() {
+        Err(Error::EmptyInputError)
+    } else {
+        Ok(Node {
+            name: root_name,
+            children: top_level,
+        })
+    }
+}
+
+fn fill_names(
+    nodes: &mut [Node],
+    parent_path: &str,
+    entries_by_path: &HashMap<String, Entry>,
+    template: &Template,
+    default: &Option<String>,
+) -> Result<(), Error> {
+    for node in nodes {
+        let path = if parent_path.is_empty() {
+            node.name.clone()
+        } else {
+            format!("{}/{}", parent_path, node.name)
+        };
+        fill_names(
+            &mut node.children,
+            &path,
+            entries_by_path,
+            template,
+            default,
+        )?;
+        // Directories without entries of their own only show up as part of
+        // their children's paths
+        let entry = entries_by_path.get(&path);
+        node.name = template.fill_with_default(
+            |placeholder_name| match placeholder_name {
+                "name" => Some(node.name.clone()),
+                "path" => Some(path.clone()),
+                "kind" => Some(entry.map_or("dir", |entry| entry.kind).to_string()),
+                "size" => entry.map(|entry| entry.size.to_string()),
+                "mode" => entry
+                    .and_then(|entry| entry.mode)
+                    .map(|mode| format!("{:04o}", mode)),
+                "permissions" => entry.and_then(|entry| entry.mode).map(format_permissions),
+                _ => None,
+            },
+            default,
+        )?;
+    }
+    Ok(())
+}
+
+fn tar_entries(bytes: &[u8]) -> Result<Vec<Entry>, Error> {
+    let reader: Box<dyn Read> = if bytes.starts_with(GZIP_MAGIC) {
+        Box::new(GzDecoder::new(bytes))
+    } else {
+        Box::new(bytes)
+    };
+    let mut archive = tar::Archive::new(reader);
+    let mut entries = Vec::new();
+    for tar_entry in archive.entries().map_err(tar_error)? {
+        let tar_entry = tar_entry.map_err(tar_error)?;
+        let header = tar_entry.header();
+        let kind = match header.entry_type() {
+            tar::EntryType::Directory => "dir",
+            tar::EntryType::Regular | tar::EntryType::Continuous | tar::EntryType::Link => "file",
+            tar::EntryType::Symlink => "symlink",
+            _ => "other",
+        };
+        entries.push(Entry {
+            path: tar_entry
+                .path()
+                .map_err(tar_error)?
+                .to_string_lossy()
+                .into_owned(),
+            size: header.size().map_err(tar_error)?,
+            mode: header.mode().ok().map(|mode| mode & 0o7777),
+            kind,
+        });
+    }
+    Ok(entries)
+}
+
+fn tar_error(io_error: std::io::Error) -> Error {
+    Error::FormatSpecificError(format!("invalid tar archive: {}", io_error))
+}
+
+fn zip_entries(bytes: &[u8]) -> Result<Vec<Entry>, Error> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(zip_error)?;
+    let mut entries = Vec::new();
+    // Only the central directory is read, so compressed contents (in whatever
+    // compression method) are never touched
+    for i in 0..archive.len() {
+        let zip_entry = archive.by_index_raw(i).map_err(zip_error)?;
+        let kind = if zip_entry.is_dir() {
+            "dir"
+        } else if zip_entry.is_symlink() {
+            "symlink"
+        } else {
+            "file"
+        };
+        entries.push(Entry {
+            path: zip_entry.name().map_err(zip_error)?.into_owned(),
+            size: zip_entry.size(),
+            mode: zip_entry.unix_mode().map(|mode| mode & 0o7777),
+            kind,
+        });
+    }
+    Ok(entries)
+}
+
+fn zip_error(zip_error: zip::result::ZipError) -> Error {
+    Error::FormatSpecificError(format!("invalid zip archive: {}", zip_error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parens;
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    // (path, contents, mode); paths ending in `/` are directories
+    const ENTRIES: &[(&str, &str, u32)] = &[
+        ("ruut-0.8.0/", "", 0o755),
+        ("ruut-0.8.0/Cargo.toml", "[package]\n", 0o644),
+        ("ruut-0.8.0/src/main.rs", "fn main() {}\n", 0o644),
+        ("ruut-0.8.0/ruut", "", 0o755),
+    ];
+
+    fn tar_bytes() -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, contents, mode) in ENTRIES {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(if path.ends_with('/') {
+                tar::EntryType::Directory
+            } else {
+                tar::EntryType::Regular
+            });
+            header.set_size(contents.len() as u64);
+            header.set_mode(*mode);
+            builder
+                .append_data(&mut header, path, contents.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn zip_bytes() -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (path, contents, mode) in ENTRIES {
+            let options = SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Stored)
+                .unix_permissions(*mode);
+            if path.ends_with('/') {
+                writer.add_directory(*path, options).unwrap();
+            } else {
+                writer.start_file(*path, options).unwrap();
+                writer.write_all(contents.as_bytes()).unwrap();
+            }
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn deserialize_archive(
+        bytes: &[u8],
+        archive_format: ArchiveFormat,
+        template: &str,
+    ) -> Result<Node, Error> {
+        archive_to_node(
+            bytes,
+            archive_format,
+            template.to_string(),
+            Some("-".to_string()),
+            "release.tar".to_string(),
+        )
+    }
+
+    #[test]
+    fn missing_file() {
+        let deserialization_err = deserialize(
+            "/definitely/not/a/real/archive.zip".to_string(),
+            ArchiveFormat::Zip,
+            "{name}".to_string(),
+            None,
+            None,
+        )
+        .unwrap_err();
+        let is_format_error = matches!(deserialization_err, Error::FormatSpecificError(_));
+        assert!(is_format_error);
+    }
+
+    #[test]
+    fn invalid_zip() {
+        let deserialization_err =
+            deserialize_archive(b"not a zip", ArchiveFormat::Zip, "{name}").unwrap_err();
+        let is_format_error = matches!(deserialization_err, Error::FormatSpecificError(_));
+        assert!(is_format_error);
+    }
+
+    #[test]
+    fn empty_tar() {
+        let empty_tar = tar::Builder::new(Vec::new()).into_inner().unwrap();
+        let deserialization_err =
+            deserialize_archive(&empty_tar, ArchiveFormat::Tar, "{name}").unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn good_tar() {
+        let expected =
+            parens::deserialize("ruut-0.8.0 (Cargo.toml, src (main.rs), ruut)".to_string())
+                .unwrap();
+        assert_eq!(
+            deserialize_archive(&tar_bytes(), ArchiveFormat::Tar, "{name}").unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn gzipped_tar_with_template() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&tar_bytes()).unwrap();
+        let tar_gz = encoder.finish().unwrap();
+        assert_eq!(
+            deserialize_archive(&tar_gz, ArchiveFormat::Tar, "{name} {permissions} {size}")
+                .unwrap(),
+            Node {
+                name: "ruut-0.8.0 rwxr-xr-x 0".to_string(),
+                children: vec![
+                    Node::new("Cargo.toml rw-r--r-- 10".to_string()),
+                    Node {
+                        name: "src - -".to_string(),
+                        children: vec![Node::new("main.rs rw-r--r-- 13".to_string())]
+                    },
+                    Node::new("ruut rwxr-xr-x 0".to_string()),
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn zip_with_template() {
+        let root_node =
+            deserialize_archive(&zip_bytes(), ArchiveFormat::Zip, "{name} ({kind}, {mode})")
+                .unwrap();
+        assert_eq!(root_node.name, "ruut-0.8.0 (dir, 0755)".to_string());
+        assert_eq!(
+            root_node.children[1],
+            Node {
+                name: "src (dir, -)".to_string(),
+                children: vec![Node::new("main.rs (file, 0644)".to_string())]
+            }
+        );
+    }
+
+    #[test]
+    fn multiple_top_level_entries() {
+        let mut builder = tar::Builder::new(Vec::new());
+        for path in ["README.md", "bin/ruut"] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(0);
+            builder
+                .append_data(&mut header, path, "".as_bytes())
+                .unwrap();
+        }
+        let tar = builder.into_inner().unwrap();
+        let expected =
+            parens::deserialize("release.tar (README.md, bin (ruut))".to_string()).unwrap();
+        assert_eq!(
+            deserialize_archive(&tar, ArchiveFormat::Tar, "{name}").unwrap(),
+            expected
+        );
+    }
+}
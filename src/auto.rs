@@ -0,0 +1,214 @@
+use super::tree::BRANCHES;
+use super::{parens, InputFormat};
+use serde_json::Value as JsonValue;
+
+const PATH_SEPARATOR: char = '/';
+
+pub struct Detection {
+    pub format: InputFormat,
+    // The name of the format as it would be passed to `-f`
+    pub format_name: &'static str,
+    pub reason: String,
+}
+
+fn detection(format: InputFormat, format_name: &'static str, reason: &str) -> Detection {
+    Detection {
+        format,
+        format_name,
+        reason: reason.to_string(),
+    }
+}
+
+// Picks the most likely format by looking at the shape of the input, from the
+// most distinctive formats to the least
+pub fn detect(serialized: &str, children_key: &str) -> Detection {
+    let trimmed = serialized.trim();
+    if trimmed.is_empty() {
+        return detection(InputFormat::Parens, "parens", "the input is empty");
+    }
+    if trimmed.starts_with(['{', '[']) {
+        if let Ok(value) = json5::from_str::<JsonValue>(trimmed) {
+            return detect_json(&value, children_key);
+        }
+    }
+    if serialized
+        .lines()
+        .any(|line| BRANCHES.iter().any(|glyph| line.contains(glyph.trim_end())))
+    {
+        return detection(
+            InputFormat::Tree,
+            "tree",
+            "a line contains a tree branch glyph (e.g. `├──`)",
+        );
+    }
+    if looks_like_parens(trimmed) {
+        return detection(
+            InputFormat::Parens,
+            "parens",
+            "the input contains balanced parentheses and parses as a parens expression",
+        );
+    }
+
+    let lines: Vec<&str> = serialized
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let indents: Vec<usize> = lines
+        .iter()
+        .map(|line| line.len() - line.trim_start().len())
+        .collect();
+    let min_indent = indents.iter().min().copied().unwrap_or_default();
+    if indents.iter().any(|indent| *indent > min_indent) {
+        detection(
+            InputFormat::Indent,
+            "indent",
+            "some lines are indented more than others",
+        )
+    } else if lines.iter().any(|line| line.contains(PATH_SEPARATOR)) {
+        detection(
+            InputFormat::Paths,
+            "paths",
+            "the lines are evenly indented and contain `/` separators",
+        )
+    } else if lines.len() > 1 {
+        // As `indent`, evenly indented lines would all be roots, while `paths`
+        // puts them under a shared root (like the output of `ls`)
+        detection(
+            InputFormat::Paths,
+            "paths",
+            "the input has several evenly indented lines, which only `paths` can put under one root",
+        )
+    } else {
+        detection(
+            InputFormat::Parens,
+            "parens",
+            "the input is a single name with nothing else to go on",
+        )
+    }
+}
+
+// The parens parser is lenient enough to accept most text, so this also checks
+// that the parentheses are balanced and that every line break is next to a `(`,
+// `,`, or `)` (as opposed to lines that just happen to contain parentheses)
+fn looks_like_parens(trimmed: &str) -> bool {
+    let mut depth: usize = 0;
+    for c in trimmed.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return false,
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    let lines: Vec<&str> = trimmed.lines().collect();
+    trimmed.contains('(')
+        && depth == 0
+        && lines.windows(2).all(|pair| {
+            pair[0].trim_end().ends_with(['(', ',', ')'])
+                || pair[1].trim_start().starts_with([')', ','])
+        })
+        && parens::deserialize(trimmed.to_string()).is_ok()
+}
+
+// A nested-key object only ever has objects (or nothing) as values, while a
+// jsonprop-shaped one holds its node's properties (like a `name` string)
+fn detect_json(value: &JsonValue, children_key: &str) -> Detection {
+    match value {
+        JsonValue::Array(_) => detection(
+            InputFormat::JsonProperties,
+            "jsonprop",
+            "the input is a JSON array, which only `jsonprop` accepts at the top level",
+        ),
+        JsonValue::Object(map) if map.contains_key(children_key) => Detection {
+            format: InputFormat::JsonProperties,
+            format_name: "jsonprop",
+            reason: format!("the JSON object has a `{}` property", children_key),
+        },
+        JsonValue::Object(map)
+            if map
+                .values()
+                .all(|value| value.is_object() || value.is_null()) =>
+        {
+            detection(
+                InputFormat::Json,
+                "json",
+                "every value in the JSON object is another object (or `null`)",
+            )
+        }
+        _ => detection(
+            InputFormat::JsonProperties,
+            "jsonprop",
+            "the JSON object has properties that aren't objects",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detected_name(serialized: &str) -> &'static str {
+        detect(serialized, "children").format_name
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            detected_name(r#"{"Parent": {"Child 1": null, "Child 2": {}}}"#),
+            "json"
+        );
+    }
+
+    #[test]
+    fn jsonprop() {
+        assert_eq!(
+            detected_name(r#"{"name": "Parent", "children": [{"name": "Child"}]}"#),
+            "jsonprop"
+        );
+        assert_eq!(detected_name(r#"[{name: "Parent"}]"#), "jsonprop");
+        assert_eq!(detected_name(r#"{"name": "Parent"}"#), "jsonprop");
+        assert_eq!(
+            detect(r#"{"name": "Parent", "kids": {}}"#, "kids").format_name,
+            "jsonprop"
+        );
+    }
+
+    #[test]
+    fn tree() {
+        assert_eq!(detected_name("Parent\n├── Child 1\n└── Child 2\n"), "tree");
+        assert_eq!(detected_name("Parent\n`-- Child\n"), "tree");
+    }
+
+    #[test]
+    fn parens() {
+        assert_eq!(
+            detected_name("Parent (Child 1, Child 2 (Grandchild))"),
+            "parens"
+        );
+        assert_eq!(
+            detected_name("Parent (\n  Child 1,\n  Child 2\n)\n"),
+            "parens"
+        );
+        assert_eq!(detected_name("Parent"), "parens");
+    }
+
+    #[test]
+    fn indent() {
+        assert_eq!(detected_name("Parent\n  Child 1\n  Child 2\n"), "indent");
+        // Lines that merely contain parentheses aren't a parens expression
+        assert_eq!(detected_name("Parent\n\tChild (1\n"), "indent");
+        assert_eq!(
+            detected_name("Parent\n  Child (1)\n  Child (2)\n"),
+            "indent"
+        );
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(
+            detected_name("  src/lib.rs\n  src/main.rs\n  Cargo.toml\n"),
+            "paths"
+        );
+        assert_eq!(detected_name("Cargo.toml\nREADME.md\nsrc\n"), "paths");
+    }
+}
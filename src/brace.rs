@@ -0,0 +1,191 @@
+use super::paths;
+use super::{Error, Node};
+
+const GROUP_OPEN: char = '{';
+const GROUP_CLOSE: char = '}';
+const ALTERNATIVE_SEPARATOR: char = ',';
+const ESCAPE: char = '\\';
+
+// A piece of an expression: either literal text or a group of alternatives,
+// each of which is itself a sequence of pieces
+enum Piece {
+    Literal(String),
+    Group(Vec<Vec<Piece>>),
+}
+
+pub fn deserialize(serialized: String, root_name: Option<String>) -> Result<Node, Error> {
+    let mut parser = Parser {
+        chars: serialized.chars().collect(),
+        pos: 0,
+        line: 1,
+    };
+    let mut expanded = Vec::new();
+    // Like in a shell, whitespace (outside of braces) separates expressions
+    loop {
+        parser.skip_whitespace();
+        if parser.peek().is_none() {
+            break;
+        }
+        let pieces = parser.parse_sequence(false)?;
+        expanded.append(&mut expand(&pieces));
+    }
+    paths::paths_to_node(expanded.iter().map(String::as_str), root_name)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser {
+    // Parses up to the end of the expression or, inside a group, up to the end
+    // of the current alternative
+    fn parse_sequence(&mut self, in_group: bool) -> Result<Vec<Piece>, Error> {
+        let mut pieces = Vec::new();
+        let mut literal = String::new();
+        while let Some(c) = self.peek() {
+            match c {
+                // A newline can't be part of a name, so the group is left for
+                // `parse_group` to report as unclosed
+                '\n' if in_group => break,
+                c if c.is_whitespace() && !in_group => break,
+                GROUP_OPEN => {
+                    let open_line = self.line;
+                    self.pos += 1;
+                    pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+                    pieces.push(self.parse_group(open_line)?);
+                    continue;
+                }
+                GROUP_CLOSE | ALTERNATIVE_SEPARATOR if in_group => break,
+                GROUP_CLOSE => {
+                    return Err(Error::FormatSpecificError(format!(
+                        "line {}: unmatched `}}`",
+                        self.line
+                    )));
+                }
+                ESCAPE => {
+                    self.pos += 1;
+                    literal.push(self.peek().unwrap_or(ESCAPE));
+                }
+                c => literal.push(c),
+            }
+            self.pos += 1;
+        }
+        pieces.push(Piece::Literal(literal));
+        Ok(pieces)
+    }
+
+    fn parse_group(&mut self, open_line: usize) -> Result<Piece, Error> {
+        let mut alternatives = Vec::new();
+        loop {
+            alternatives.push(self.parse_sequence(true)?);
+            match self.peek() {
+                Some(ALTERNATIVE_SEPARATOR) => self.pos += 1,
+                Some(GROUP_CLOSE) => {
+                    self.pos += 1;
+                    return Ok(Piece::Group(alternatives));
+                }
+                _ => {
+                    return Err(Error::FormatSpecificError(format!(
+                        "line {}: `{{` missing closing `}}`",
+                        open_line
+                    )));
+                }
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            if c == '\n' {
+                self.line += 1;
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+}
+
+fn expand(pieces: &[Piece]) -> Vec<String> {
+    let mut expanded = vec![String::new()];
+    for piece in pieces {
+        expanded = match piece {
+            Piece::Literal(literal) => expanded
+                .into_iter()
+                .map(|prefix| prefix + literal)
+                .collect(),
+            Piece::Group(alternatives) => {
+                let suffixes: Vec<String> = alternatives
+                    .iter()
+                    .flat_map(|alternative| expand(alternative))
+                    .collect();
+                expanded
+                    .iter()
+                    .flat_map(|prefix| suffixes.iter().map(move |suffix| prefix.clone() + suffix))
+                    .collect()
+            }
+        };
+    }
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parens;
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let deserialization_err = deserialize(" \n ".to_string(), None).unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn missing_closing_brace() {
+        let brace = "src/lib.rs\nsrc/{main.rs,parens/{parser,tokenizer.rs}\n";
+        let deserialization_err = deserialize(brace.to_string(), None).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("line 2: `{` missing closing `}`".to_string())
+        );
+    }
+
+    #[test]
+    fn unmatched_closing_brace() {
+        let deserialization_err = deserialize("src/{a,b}}".to_string(), None).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("line 1: unmatched `}`".to_string())
+        );
+    }
+
+    #[test]
+    fn nested_groups() {
+        let brace = "src/{lib.rs,parens/{parser,tokenizer}.rs}";
+        let expected =
+            parens::deserialize("src (lib.rs, parens (parser.rs, tokenizer.rs))".to_string())
+                .unwrap();
+        assert_eq!(deserialize(brace.to_string(), None).unwrap(), expected);
+    }
+
+    #[test]
+    fn multiple_expressions_merged() {
+        let brace = r"
+            src/{main,lib}.rs src/parens.rs
+            docs/{User\ Guide.md,FAQ{,.old}.md}
+        ";
+        let expected = parens::deserialize(
+            "ruut (src (main.rs, lib.rs, parens.rs), docs (User Guide.md, FAQ.md, FAQ.old.md))"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            deserialize(brace.to_string(), Some("ruut".to_string())).unwrap(),
+            expected
+        );
+    }
+}
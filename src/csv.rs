@@ -0,0 +1,245 @@
+use super::edges::{self, Edge, Vertex};
+use super::template::Template;
+use super::{Error, Node};
+use csv::{ReaderBuilder, StringRecord, Trim};
+
+pub const DEFAULT_ID_COLUMN: &str = "id";
+pub const DEFAULT_PARENT_COLUMN: &str = "parent_id";
+const DEFAULT_LABEL_COLUMN: &str = "label";
+const DEFAULT_TEMPLATE: &str = "{name}";
+const DEFAULT_NAME_COLUMN: &str = "name";
+
+pub fn deserialize(
+    serialized: String,
+    delimiter: u8,
+    id_column: String,
+    parent_column: String,
+    label_column: Option<String>,
+    template_str: String,
+    default: Option<String>,
+) -> Result<Node, Error> {
+    if serialized.trim().is_empty() {
+        return Err(Error::EmptyInputError);
+    }
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .trim(Trim::All)
+        .from_reader(serialized.as_bytes());
+    let headers = reader.headers()?.clone();
+    let id_idx = column_index(&headers, &id_column)?;
+    let parent_idx = column_index(&headers, &parent_column)?;
+    // When the default template has no `name` column to fill it, a `label`
+    // column names the nodes instead, like `id` and `parent_id` do for the
+    // other columns
+    let has_column = |column: &str| headers.iter().any(|header| header == column);
+    let label_column = label_column.or_else(|| {
+        Some(DEFAULT_LABEL_COLUMN.to_string()).filter(|label_column| {
+            template_str == DEFAULT_TEMPLATE
+                && !has_column(DEFAULT_NAME_COLUMN)
+                && has_column(label_column)
+        })
+    });
+    let label_idx = label_column
+        .map(|label_column| column_index(&headers, &label_column))
+        .transpose()?;
+    let template = Template::new(template_str)?;
+
+    let mut vertices = Vec::new();
+    let mut edges = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map_or(0, |position| position.line());
+        let id = record.get(id_idx).unwrap_or_default().to_string();
+        if id.is_empty() {
+            return Err(Error::FormatSpecificError(format!(
+                "line {}: row is missing an id",
+                line
+            )));
+        }
+        let name = match label_idx {
+            Some(label_idx) => record.get(label_idx).unwrap_or_default().to_string(),
+            None => template.fill_with_default(
+                |placeholder_name| {
+                    headers
+                        .iter()
+                        .position(|header| header == placeholder_name)
+                        .and_then(|idx| record.get(idx))
+                        .map(str::to_string)
+                },
+                &default,
+            )?,
+        };
+        let parent_id = record.get(parent_idx).unwrap_or_default();
+        // Rows without a parent are roots
+        if !parent_id.is_empty() {
+            edges.push(Edge {
+                parent: parent_id.to_string(),
+                child: id.clone(),
+                line: Some(line),
+            });
+        }
+        vertices.push(Vertex {
+            id,
+            name,
+            line: Some(line),
+        });
+    }
+    edges::edges_to_node(vertices, edges)
+}
+
+fn column_index(headers: &StringRecord, column: &str) -> Result<usize, Error> {
+    headers
+        .iter()
+        .position(|header| header == column)
+        .ok_or_else(|| Error::FormatSpecificError(format!("missing `{}` column", column)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parens;
+    use super::*;
+
+    fn deserialize_csv(csv: &str) -> Result<Node, Error> {
+        deserialize(
+            csv.trim_start().to_string(),
+            b',',
+            DEFAULT_ID_COLUMN.to_string(),
+            DEFAULT_PARENT_COLUMN.to_string(),
+            None,
+            "{name}".to_string(),
+            None,
+        )
+    }
+
+    #[test]
+    fn zero_length_csv() {
+        let deserialization_err = deserialize_csv("").unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn header_only_csv() {
+        let deserialization_err = deserialize_csv("id,parent_id,label\n").unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn missing_column() {
+        let deserialization_err = deserialize_csv("id,label\n1,Parent\n").unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("missing `parent_id` column".to_string())
+        );
+    }
+
+    #[test]
+    fn multiple_roots() {
+        let csv = "id,parent_id,label\n1,,Parent\n2,,Other Parent\n";
+        let deserialization_err = deserialize_csv(csv).unwrap_err();
+        assert_eq!(deserialization_err, Error::MultipleRootsError);
+    }
+
+    #[test]
+    fn orphan() {
+        let csv = "id,parent_id,label\n1,,Parent\n2,7,Orphan\n";
+        let deserialization_err = deserialize_csv(csv).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("line 3: parent `7` of `2` does not exist".to_string())
+        );
+    }
+
+    #[test]
+    fn duplicate_id() {
+        let csv = "id,parent_id,label\n1,,Parent\n2,1,Child\n2,1,Other Child\n";
+        let deserialization_err = deserialize_csv(csv).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("line 4: duplicate id `2`".to_string())
+        );
+    }
+
+    #[test]
+    fn cycle() {
+        let csv = "id,parent_id,label\n1,,Parent\n2,4,A\n3,2,B\n4,3,C\n5,4,D\n";
+        let deserialization_err = deserialize_csv(csv).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("line 3: `2` is part of a cycle".to_string())
+        );
+    }
+
+    #[test]
+    fn cycle_without_root() {
+        let csv = "id,parent_id,label\n1,2,A\n2,1,B\n";
+        let deserialization_err = deserialize_csv(csv).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("line 2: `1` is part of a cycle".to_string())
+        );
+    }
+
+    #[test]
+    fn good_csv() {
+        let csv = r#"
+id, parent_id, label
+6, 5, Grandchild 3
+1, , Parent
+2, 1, Child 1
+3, 1,"Child 2"
+4, 3, Grandchild 1
+7, 3, Grandchild 2
+5, 1, Child 3
+"#;
+        let expected = parens::deserialize(
+            "Parent (Child 1, Child 2 (Grandchild 1, Grandchild 2), Child 3 (Grandchild 3))"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(deserialize_csv(csv).unwrap(), expected);
+    }
+
+    #[test]
+    fn label_column_by_default() {
+        let csv = "id,parent_id,label\n1,,A\n2,1,B\n";
+        let expected = parens::deserialize("A (B)".to_string()).unwrap();
+        assert_eq!(deserialize_csv(csv).unwrap(), expected);
+
+        let csv = "id,parent_id,name,label\n1,,Ada,A\n2,1,Grace,B\n";
+        let expected = parens::deserialize("Ada (Grace)".to_string()).unwrap();
+        assert_eq!(deserialize_csv(csv).unwrap(), expected);
+        let root_node = deserialize(
+            csv.to_string(),
+            b',',
+            DEFAULT_ID_COLUMN.to_string(),
+            DEFAULT_PARENT_COLUMN.to_string(),
+            None,
+            "{name} ({label})".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(root_node.name, "Ada (A)".to_string());
+    }
+
+    #[test]
+    fn tsv_with_template() {
+        let tsv = "key\tboss\tname\ttitle\nceo\t\tAda\tCEO\ncto\tceo\tGrace\tCTO\n";
+        let root_node = deserialize(
+            tsv.to_string(),
+            b'\t',
+            "key".to_string(),
+            "boss".to_string(),
+            None,
+            "{name} ({title}, {team})".to_string(),
+            Some("?".to_string()),
+        )
+        .unwrap();
+        assert_eq!(
+            root_node,
+            Node {
+                name: "Ada (CEO, ?)".to_string(),
+                children: vec![Node::new("Grace (CTO, ?)".to_string())]
+            }
+        );
+    }
+}
@@ -0,0 +1,121 @@
+mod parser;
+mod tokenizer;
+use super::edges::{self, Edge, Vertex};
+use super::{Error, Node};
+use parser::Graph;
+
+pub fn deserialize(serialized: String) -> Result<Node, Error> {
+    let tokens = tokenizer::tokenize(&serialized)?;
+    let graph = parser::parse(tokens)?;
+    graph_to_node(graph)
+}
+
+fn graph_to_node(graph: Graph) -> Result<Node, Error> {
+    let vertices = graph
+        .nodes
+        .iter()
+        .map(|id| Vertex {
+            id: id.clone(),
+            name: graph
+                .labels
+                .get(id)
+                .map_or_else(|| id.clone(), |label| format_label(label, id)),
+            line: None,
+        })
+        .collect();
+    let edges = graph
+        .edges
+        .into_iter()
+        .map(|(parent, child)| Edge {
+            parent,
+            child,
+            line: None,
+        })
+        .collect();
+    edges::edges_to_node(vertices, edges)
+}
+
+// Labels can use `\N` for the node's id and `\n`, `\l`, and `\r` for line
+// breaks, which don't fit on a single line of a tree
+fn format_label(label: &str, id: &str) -> String {
+    label
+        .replace("\\N", id)
+        .replace("\\n", " ")
+        .replace("\\l", " ")
+        .replace("\\r", " ")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parens;
+    use super::*;
+
+    #[test]
+    fn empty_graph() {
+        let deserialization_err = deserialize("digraph {}".to_string()).unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn multiple_roots() {
+        let dot = "digraph { a -> b; c -> d }";
+        let deserialization_err = deserialize(dot.to_string()).unwrap_err();
+        assert_eq!(deserialization_err, Error::MultipleRootsError);
+    }
+
+    #[test]
+    fn multiple_parents() {
+        let dot = "digraph { a -> b; a -> c; b -> d; c -> d }";
+        let deserialization_err = deserialize(dot.to_string()).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("`d` has multiple parents (`b` and `c`)".to_string())
+        );
+    }
+
+    #[test]
+    fn cycle() {
+        let dot = "digraph { root -> a; b -> c -> d -> b }";
+        let deserialization_err = deserialize(dot.to_string()).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("`b` is part of a cycle".to_string())
+        );
+    }
+
+    #[test]
+    fn cycle_without_root() {
+        let dot = "digraph { a -> a }";
+        let deserialization_err = deserialize(dot.to_string()).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("`a` is part of a cycle".to_string())
+        );
+    }
+
+    #[test]
+    fn good_dot() {
+        let dot = r#"
+            // Generated by some tool
+            digraph "structure" {
+                node [shape=box];
+                p [label="Parent"];
+                c1 [label="Child 1"]; c2 [label="Child\n2"]; c3 [label=<Child 3>];
+                p -> { c1 c2 } [color=red];
+                p -> c3;
+                c2 -> "Grandchild 1";
+                c2 -> "Grandchild 2";
+                c2 -> "Grandchild 1";
+                c3 -> g3;
+                g3 [label="Grand\N"];
+            }
+        "#;
+        let expected = parens::deserialize(
+            "Parent (Child 1, Child 2 (Grandchild 1, Grandchild 2), Child 3 (Grandg3))".to_string(),
+        )
+        .unwrap();
+        assert_eq!(deserialize(dot.to_string()).unwrap(), expected);
+    }
+}
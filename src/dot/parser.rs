@@ -0,0 +1,348 @@
+use super::super::Error;
+use super::tokenizer::Token;
+use std::collections::{HashMap, HashSet};
+use std::iter::Peekable;
+use std::vec;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Graph {
+    // Node ids in the order they first appear
+    pub nodes: Vec<String>,
+    pub labels: HashMap<String, String>,
+    pub edges: Vec<(String, String)>,
+}
+
+pub fn parse(tokens: Vec<Token>) -> Result<Graph, Error> {
+    if tokens.is_empty() {
+        return Err(Error::EmptyInputError);
+    }
+    let mut parser = Parser {
+        tokens: tokens.into_iter().peekable(),
+        graph: Graph::default(),
+        seen_nodes: HashSet::new(),
+    };
+    parser.parse_graph()?;
+    Ok(parser.graph)
+}
+
+struct Parser {
+    tokens: Peekable<vec::IntoIter<Token>>,
+    graph: Graph,
+    seen_nodes: HashSet<String>,
+}
+
+impl Parser {
+    fn parse_graph(&mut self) -> Result<(), Error> {
+        if self.peek_keyword("strict") {
+            self.tokens.next();
+        }
+        if self.peek_keyword("graph") {
+            return Err(format_error(
+                "only directed graphs (`digraph`) are supported",
+            ));
+        }
+        if !self.peek_keyword("digraph") {
+            return Err(format_error("expected `digraph`"));
+        }
+        self.tokens.next();
+        if let Some(Token::Id(_)) | Some(Token::QuotedId(_)) = self.tokens.peek() {
+            self.tokens.next();
+        }
+        self.expect(Token::BraceOpen, "`{`")?;
+        self.parse_stmt_list()?;
+        match self.tokens.next() {
+            None => Ok(()),
+            Some(token) => Err(unexpected(&token)),
+        }
+    }
+
+    // Parses statements up to and including the closing `}`, returning every
+    // node mentioned so that subgraphs can be used as edge endpoints
+    fn parse_stmt_list(&mut self) -> Result<Vec<String>, Error> {
+        let mut mentioned = Vec::new();
+        loop {
+            match self.tokens.peek() {
+                None => return Err(format_error("missing closing `}`")),
+                Some(Token::BraceClose) => {
+                    self.tokens.next();
+                    return Ok(mentioned);
+                }
+                Some(Token::Semicolon) | Some(Token::Comma) => {
+                    self.tokens.next();
+                }
+                Some(_) => mentioned.append(&mut self.parse_stmt()?),
+            }
+        }
+    }
+
+    fn parse_stmt(&mut self) -> Result<Vec<String>, Error> {
+        if ["graph", "node", "edge"]
+            .iter()
+            .any(|keyword| self.peek_keyword(keyword))
+        {
+            // Default attributes don't affect the structure
+            self.tokens.next();
+            self.parse_attr_list()?;
+            return Ok(Vec::new());
+        }
+        let is_subgraph = self.peek_keyword("subgraph");
+        let first_id = match self.tokens.peek() {
+            Some(Token::Id(id)) | Some(Token::QuotedId(id)) if !is_subgraph => Some(id.clone()),
+            _ => None,
+        };
+        let mut mentioned = match first_id {
+            Some(id) => {
+                self.tokens.next();
+                if let Some(Token::Equals) = self.tokens.peek() {
+                    // A graph attribute like `rankdir = LR`
+                    self.tokens.next();
+                    self.expect_id()?;
+                    return Ok(Vec::new());
+                }
+                self.skip_port()?;
+                self.add_node(&id);
+                vec![id]
+            }
+            None => self.parse_subgraph()?,
+        };
+
+        let mut sources = mentioned.clone();
+        let mut is_edge_stmt = false;
+        loop {
+            match self.tokens.peek() {
+                Some(Token::DirectedEdge) => {
+                    self.tokens.next();
+                    is_edge_stmt = true;
+                    let targets = self.parse_endpoint()?;
+                    for source in &sources {
+                        for target in &targets {
+                            self.graph.edges.push((source.clone(), target.clone()));
+                        }
+                    }
+                    mentioned.extend(targets.iter().cloned());
+                    sources = targets;
+                }
+                Some(Token::UndirectedEdge) => {
+                    return Err(format_error("`--` edges aren't allowed in a digraph"));
+                }
+                _ => break,
+            }
+        }
+
+        let attrs = self.parse_attr_list()?;
+        if !is_edge_stmt && mentioned.len() == 1 {
+            if let Some((_, label)) = attrs.into_iter().find(|(key, _)| key == "label") {
+                self.graph.labels.insert(mentioned[0].clone(), label);
+            }
+        }
+        Ok(mentioned)
+    }
+
+    fn parse_endpoint(&mut self) -> Result<Vec<String>, Error> {
+        if self.peek_keyword("subgraph") || self.tokens.peek() == Some(&Token::BraceOpen) {
+            return self.parse_subgraph();
+        }
+        let id = self.expect_id()?;
+        self.skip_port()?;
+        self.add_node(&id);
+        Ok(vec![id])
+    }
+
+    fn parse_subgraph(&mut self) -> Result<Vec<String>, Error> {
+        if self.peek_keyword("subgraph") {
+            self.tokens.next();
+            if let Some(Token::Id(_)) | Some(Token::QuotedId(_)) = self.tokens.peek() {
+                self.tokens.next();
+            }
+        }
+        self.expect(Token::BraceOpen, "`{`")?;
+        self.parse_stmt_list()
+    }
+
+    fn parse_attr_list(&mut self) -> Result<Vec<(String, String)>, Error> {
+        let mut attrs = Vec::new();
+        while let Some(Token::BracketOpen) = self.tokens.peek() {
+            self.tokens.next();
+            loop {
+                match self.tokens.peek() {
+                    Some(Token::BracketClose) => {
+                        self.tokens.next();
+                        break;
+                    }
+                    Some(Token::Semicolon) | Some(Token::Comma) => {
+                        self.tokens.next();
+                    }
+                    _ => {
+                        let key = self.expect_id()?;
+                        self.expect(Token::Equals, "`=`")?;
+                        let value = self.expect_id()?;
+                        attrs.push((key, value));
+                    }
+                }
+            }
+        }
+        Ok(attrs)
+    }
+
+    // Ports (`node:port:compass`) only affect where edges are drawn
+    fn skip_port(&mut self) -> Result<(), Error> {
+        while let Some(Token::Colon) = self.tokens.peek() {
+            self.tokens.next();
+            self.expect_id()?;
+        }
+        Ok(())
+    }
+
+    fn add_node(&mut self, id: &str) {
+        if self.seen_nodes.insert(id.to_string()) {
+            self.graph.nodes.push(id.to_string());
+        }
+    }
+
+    fn peek_keyword(&mut self, keyword: &str) -> bool {
+        matches!(self.tokens.peek(), Some(Token::Id(id)) if id.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect(&mut self, expected: Token, description: &str) -> Result<(), Error> {
+        match self.tokens.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format_error(&format!(
+                "expected {} but found {}",
+                description,
+                describe(&token)
+            ))),
+            None => Err(format_error(&format!(
+                "expected {} but reached the end of the input",
+                description
+            ))),
+        }
+    }
+
+    fn expect_id(&mut self) -> Result<String, Error> {
+        match self.tokens.next() {
+            Some(Token::Id(id)) | Some(Token::QuotedId(id)) => Ok(id),
+            Some(token) => Err(unexpected(&token)),
+            None => Err(format_error("unexpected end of input")),
+        }
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::BraceOpen => "`{`".to_string(),
+        Token::BraceClose => "`}`".to_string(),
+        Token::BracketOpen => "`[`".to_string(),
+        Token::BracketClose => "`]`".to_string(),
+        Token::Equals => "`=`".to_string(),
+        Token::Semicolon => "`;`".to_string(),
+        Token::Comma => "`,`".to_string(),
+        Token::Colon => "`:`".to_string(),
+        Token::DirectedEdge => "`->`".to_string(),
+        Token::UndirectedEdge => "`--`".to_string(),
+        Token::Id(id) | Token::QuotedId(id) => format!("`{}`", id),
+    }
+}
+
+fn unexpected(token: &Token) -> Error {
+    format_error(&format!("unexpected {}", describe(token)))
+}
+
+fn format_error(message: &str) -> Error {
+    Error::FormatSpecificError(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(id: &str) -> Token {
+        Token::Id(id.to_string())
+    }
+
+    #[test]
+    fn empty() {
+        let parser_error = parse(Vec::<Token>::new()).unwrap_err();
+        assert_eq!(parser_error, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn undirected_graph() {
+        let parser_error =
+            parse(vec![id("graph"), Token::BraceOpen, Token::BraceClose]).unwrap_err();
+        assert_eq!(
+            parser_error,
+            Error::FormatSpecificError(
+                "only directed graphs (`digraph`) are supported".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn missing_closing_brace() {
+        let parser_error = parse(vec![id("digraph"), Token::BraceOpen, id("a")]).unwrap_err();
+        assert_eq!(
+            parser_error,
+            Error::FormatSpecificError("missing closing `}`".to_string())
+        );
+    }
+
+    #[test]
+    fn good_token_sequence() {
+        // digraph G { rankdir=LR; node [shape=box]; a [label="A"]; a -> {b c} -> d:n; }
+        let tokens = vec![
+            id("digraph"),
+            id("G"),
+            Token::BraceOpen,
+            id("rankdir"),
+            Token::Equals,
+            id("LR"),
+            Token::Semicolon,
+            id("node"),
+            Token::BracketOpen,
+            id("shape"),
+            Token::Equals,
+            id("box"),
+            Token::BracketClose,
+            Token::Semicolon,
+            id("a"),
+            Token::BracketOpen,
+            id("label"),
+            Token::Equals,
+            Token::QuotedId("A".to_string()),
+            Token::BracketClose,
+            Token::Semicolon,
+            id("a"),
+            Token::DirectedEdge,
+            Token::BraceOpen,
+            id("b"),
+            id("c"),
+            Token::BraceClose,
+            Token::DirectedEdge,
+            id("d"),
+            Token::Colon,
+            id("n"),
+            Token::Semicolon,
+            Token::BraceClose,
+        ];
+        let graph = parse(tokens).unwrap();
+        let pair = |from: &str, to: &str| (from.to_string(), to.to_string());
+        assert_eq!(
+            graph,
+            Graph {
+                nodes: vec![
+                    "a".to_string(),
+                    "b".to_string(),
+                    "c".to_string(),
+                    "d".to_string()
+                ],
+                labels: vec![pair("a", "A")].into_iter().collect(),
+                edges: vec![
+                    pair("a", "b"),
+                    pair("a", "c"),
+                    pair("b", "d"),
+                    pair("c", "d")
+                ],
+            }
+        );
+    }
+}
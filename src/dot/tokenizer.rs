@@ -0,0 +1,225 @@
+use super::super::Error;
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(Debug, PartialEq)]
+pub enum Token {
+    BraceOpen,
+    BraceClose,
+    BracketOpen,
+    BracketClose,
+    Equals,
+    Semicolon,
+    Comma,
+    Colon,
+    DirectedEdge,
+    UndirectedEdge,
+    // Bare identifiers and numerals, which can also be keywords
+    Id(String),
+    // Double-quoted and HTML-like (`<...>`) strings, which are never keywords
+    QuotedId(String),
+}
+
+pub fn tokenize(serialized: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    let mut chars = serialized.chars().peekable();
+    let mut at_line_start = true;
+    while let Some(c) = chars.next() {
+        let was_at_line_start = at_line_start;
+        at_line_start = c == '\n' || (at_line_start && c.is_whitespace());
+        match c {
+            c if c.is_whitespace() => {}
+            // Lines starting with `#` are C preprocessor output and ignored
+            '#' if was_at_line_start => skip_line(&mut chars),
+            '/' if chars.peek() == Some(&'/') => skip_line(&mut chars),
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                skip_block_comment(&mut chars)?;
+            }
+            '{' => tokens.push(Token::BraceOpen),
+            '}' => tokens.push(Token::BraceClose),
+            '[' => tokens.push(Token::BracketOpen),
+            ']' => tokens.push(Token::BracketClose),
+            '=' => tokens.push(Token::Equals),
+            ';' => tokens.push(Token::Semicolon),
+            ',' => tokens.push(Token::Comma),
+            ':' => tokens.push(Token::Colon),
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                tokens.push(Token::DirectedEdge);
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                chars.next();
+                tokens.push(Token::UndirectedEdge);
+            }
+            '"' => {
+                let mut quoted = read_quoted(&mut chars)?;
+                // `"a" + "b"` is concatenated into a single string
+                while skip_concatenation(&mut chars) {
+                    quoted.push_str(&read_quoted(&mut chars)?);
+                }
+                tokens.push(Token::QuotedId(quoted));
+            }
+            '<' => tokens.push(Token::QuotedId(read_html(&mut chars)?)),
+            c if is_id_char(c) || c == '-' || c == '.' => {
+                let mut id = c.to_string();
+                while let Some(next) = chars.peek() {
+                    if is_id_char(*next) || *next == '.' {
+                        id.push(*next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Id(id));
+            }
+            c => {
+                return Err(Error::FormatSpecificError(format!(
+                    "unexpected character `{}`",
+                    c
+                )));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn skip_line(chars: &mut Peekable<Chars>) {
+    for c in chars.by_ref() {
+        if c == '\n' {
+            break;
+        }
+    }
+}
+
+fn skip_block_comment(chars: &mut Peekable<Chars>) -> Result<(), Error> {
+    while let Some(c) = chars.next() {
+        if c == '*' && chars.peek() == Some(&'/') {
+            chars.next();
+            return Ok(());
+        }
+    }
+    Err(Error::FormatSpecificError(
+        "comment missing closing `*/`".to_string(),
+    ))
+}
+
+// Reads the rest of a double-quoted string, where only `\"` is an escape
+// (other backslashes, like in `\n`, are left for labels to interpret)
+fn read_quoted(chars: &mut Peekable<Chars>) -> Result<String, Error> {
+    let mut quoted = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Ok(quoted),
+            '\\' if chars.peek() == Some(&'"') => {
+                quoted.push('"');
+                chars.next();
+            }
+            // Escaped newlines continue the string on the next line
+            '\\' if chars.peek() == Some(&'\n') => {
+                chars.next();
+            }
+            c => quoted.push(c),
+        }
+    }
+    Err(Error::FormatSpecificError(
+        "string missing closing `\"`".to_string(),
+    ))
+}
+
+// Consumes a `+` and the opening quote of the string after it, if that's what
+// comes next
+fn skip_concatenation(chars: &mut Peekable<Chars>) -> bool {
+    let mut lookahead = chars.clone();
+    while lookahead.peek().is_some_and(|c| c.is_whitespace()) {
+        lookahead.next();
+    }
+    if lookahead.next() != Some('+') {
+        return false;
+    }
+    while lookahead.peek().is_some_and(|c| c.is_whitespace()) {
+        lookahead.next();
+    }
+    if lookahead.next() != Some('"') {
+        return false;
+    }
+    *chars = lookahead;
+    true
+}
+
+fn read_html(chars: &mut Peekable<Chars>) -> Result<String, Error> {
+    let mut html = String::new();
+    let mut depth = 1;
+    for c in chars.by_ref() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(html);
+                }
+            }
+            _ => {}
+        }
+        html.push(c);
+    }
+    Err(Error::FormatSpecificError(
+        "HTML string missing closing `>`".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_string() {
+        assert_eq!(tokenize(""), Ok(Vec::<Token>::new()));
+    }
+
+    #[test]
+    fn simple_edge() {
+        assert_eq!(
+            tokenize("digraph { a -> \"b c\" [label=<B>]; }"),
+            Ok(vec![
+                Token::Id("digraph".to_string()),
+                Token::BraceOpen,
+                Token::Id("a".to_string()),
+                Token::DirectedEdge,
+                Token::QuotedId("b c".to_string()),
+                Token::BracketOpen,
+                Token::Id("label".to_string()),
+                Token::Equals,
+                Token::QuotedId("B".to_string()),
+                Token::BracketClose,
+                Token::Semicolon,
+                Token::BraceClose,
+            ])
+        );
+    }
+
+    #[test]
+    fn comments_escapes_and_concatenation() {
+        assert_eq!(
+            tokenize("# preprocessor\n/* block */ \"say \\\"hi\\\"\" + \" there\" // line\n-1.5"),
+            Ok(vec![
+                Token::QuotedId("say \"hi\" there".to_string()),
+                Token::Id("-1.5".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn unterminated_string() {
+        assert_eq!(
+            tokenize("digraph { \"a }"),
+            Err(Error::FormatSpecificError(
+                "string missing closing `\"`".to_string()
+            ))
+        );
+    }
+}
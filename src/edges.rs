@@ -0,0 +1,125 @@
+use super::{Error, Node};
+use std::collections::HashMap;
+
+// A node of a format that lists nodes and their parent-child edges separately
+// (e.g. `csv` or `dot`), along with the line it's on, if there is one
+pub(crate) struct Vertex {
+    pub id: String,
+    pub name: String,
+    pub line: Option<u64>,
+}
+
+pub(crate) struct Edge {
+    pub parent: String,
+    pub child: String,
+    pub line: Option<u64>,
+}
+
+// Builds the tree out of the vertices and edges, checking that they really do
+// form one: ids are unique, every parent exists, nobody has two parents, and
+// there's exactly one root with no cycles. Children are in the order of their
+// edges.
+pub(crate) fn edges_to_node(vertices: Vec<Vertex>, edges: Vec<Edge>) -> Result<Node, Error> {
+    let mut idx_by_id: HashMap<&str, usize> = HashMap::new();
+    for (idx, vertex) in vertices.iter().enumerate() {
+        if idx_by_id.insert(&vertex.id, idx).is_some() {
+            return Err(located(
+                vertex.line,
+                format!("duplicate id `{}`", vertex.id),
+            ));
+        }
+    }
+
+    let mut parent_idxs: Vec<Option<usize>> = vec![None; vertices.len()];
+    let mut children_idxs: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+    for edge in &edges {
+        let (parent_idx, child_idx) = match (
+            idx_by_id.get(edge.parent.as_str()),
+            idx_by_id.get(edge.child.as_str()),
+        ) {
+            (Some(parent_idx), Some(child_idx)) => (*parent_idx, *child_idx),
+            _ => {
+                return Err(located(
+                    edge.line,
+                    format!(
+                        "parent `{}` of `{}` does not exist",
+                        edge.parent, edge.child
+                    ),
+                ));
+            }
+        };
+        match parent_idxs[child_idx] {
+            // The same edge written twice
+            Some(prev_parent_idx) if prev_parent_idx == parent_idx => continue,
+            Some(prev_parent_idx) => {
+                return Err(located(
+                    edge.line,
+                    format!(
+                        "`{}` has multiple parents (`{}` and `{}`)",
+                        edge.child, vertices[prev_parent_idx].id, edge.parent
+                    ),
+                ));
+            }
+            None => {
+                parent_idxs[child_idx] = Some(parent_idx);
+                children_idxs[parent_idx].push(child_idx);
+            }
+        }
+    }
+
+    let mut roots = (0..vertices.len()).filter(|idx| parent_idxs[*idx].is_none());
+    let root_idx = match (roots.next(), roots.next()) {
+        (Some(root_idx), None) => root_idx,
+        (Some(_), Some(_)) => return Err(Error::MultipleRootsError),
+        (None, _) if vertices.is_empty() => return Err(Error::EmptyInputError),
+        (None, _) => return Err(cycle_error(&vertices, &parent_idxs, 0)),
+    };
+
+    let mut visited = vec![false; vertices.len()];
+    let root = build_node(root_idx, &vertices, &children_idxs, &mut visited);
+    // Every vertex with a parent that still wasn't reached from the root has
+    // to be hanging off of a cycle
+    match visited.iter().position(|was_visited| !was_visited) {
+        Some(unvisited_idx) => Err(cycle_error(&vertices, &parent_idxs, unvisited_idx)),
+        None => Ok(root),
+    }
+}
+
+fn build_node(
+    idx: usize,
+    vertices: &[Vertex],
+    children_idxs: &[Vec<usize>],
+    visited: &mut Vec<bool>,
+) -> Node {
+    visited[idx] = true;
+    Node {
+        name: vertices[idx].name.clone(),
+        children: children_idxs[idx]
+            .iter()
+            .map(|child_idx| build_node(*child_idx, vertices, children_idxs, visited))
+            .collect(),
+    }
+}
+
+// Follows parents up from `start_idx` until it finds one it's already seen,
+// which is guaranteed to be part of the cycle
+fn cycle_error(vertices: &[Vertex], parent_idxs: &[Option<usize>], start_idx: usize) -> Error {
+    let mut seen = vec![false; vertices.len()];
+    let mut idx = start_idx;
+    while !seen[idx] {
+        seen[idx] = true;
+        // Only vertices with parents can be left over
+        idx = parent_idxs[idx].unwrap();
+    }
+    located(
+        vertices[idx].line,
+        format!("`{}` is part of a cycle", vertices[idx].id),
+    )
+}
+
+fn located(line: Option<u64>, message: String) -> Error {
+    Error::FormatSpecificError(match line {
+        Some(line) => format!("line {}: {}", line, message),
+        None => message,
+    })
+}
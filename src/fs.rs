@@ -0,0 +1,369 @@
+use super::indent::{self, Line};
+use super::template::Template;
+use super::{Error, Node};
+use ignore::{DirEntry, WalkBuilder};
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+
+const LOOP_MARKER: &str = " [recursive, not followed]";
+const ERROR_MARKER: &str = " [error reading entry]";
+const OPEN_DIR_ERROR_MARKER: &str = " [error opening dir]";
+
+pub struct Settings {
+    pub hidden: bool,
+    pub gitignore: bool,
+    pub follow_symlinks: bool,
+}
+
+pub fn deserialize(
+    serialized: String,
+    template_str: String,
+    default: Option<String>,
+    settings: Settings,
+) -> Result<Node, Error> {
+    let root_path = serialized.trim();
+    if root_path.is_empty() {
+        return Err(Error::EmptyInputError);
+    }
+    let template = Template::new(template_str)?;
+    let walker = WalkBuilder::new(root_path)
+        .standard_filters(false)
+        .hidden(!settings.hidden)
+        .git_ignore(settings.gitignore)
+        .git_exclude(settings.gitignore)
+        .git_global(settings.gitignore)
+        .parents(settings.gitignore)
+        .require_git(false)
+        .follow_links(settings.follow_symlinks)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .build();
+
+    let mut lines: Vec<Line> = Vec::new();
+    let mut last_path = None;
+    for (i, result) in walker.enumerate() {
+        match result {
+            Ok(entry) => {
+                let name = if entry.depth() == 0 {
+                    root_path.to_string()
+                } else {
+                    entry.file_name().to_string_lossy().into_owned()
+                };
+                lines.push(Line {
+                    number: i + 1,
+                    depth: entry.depth(),
+                    name: get_name(&entry, name, &template, &default)?,
+                });
+                last_path = Some(entry.into_path());
+            }
+            // A directory that can't be read has already been listed itself
+            Err(walk_error) if last_path.is_some() && error_path(&walk_error) == last_path => {
+                if let Some(last_line) = lines.last_mut() {
+                    last_line.name.push_str(OPEN_DIR_ERROR_MARKER);
+                }
+            }
+            Err(walk_error) => lines.push(error_line(i + 1, &walk_error, &template, &default)?),
+        }
+    }
+    indent::lines_to_node(lines)
+}
+
+// Entries that can't be read (like dangling symlinks when following them) are
+// listed with a marker instead of stopping the whole walk, as are symlinks
+// back up into one of their own ancestors, since following them would go on
+// forever (like `tree -l` does)
+fn error_line(
+    number: usize,
+    walk_error: &ignore::Error,
+    template: &Template,
+    default: &Option<String>,
+) -> Result<Line, Error> {
+    let mut depth = None;
+    let mut path = None;
+    let mut cur_error = walk_error;
+    let marker = loop {
+        match cur_error {
+            ignore::Error::WithDepth { depth: d, err } => {
+                depth = depth.or(Some(*d));
+                cur_error = err;
+            }
+            ignore::Error::WithPath { path: p, err } => {
+                path = path.or_else(|| Some(p.clone()));
+                cur_error = err;
+            }
+            ignore::Error::Loop { child, .. } => {
+                path = Some(child.clone());
+                break LOOP_MARKER;
+            }
+            _ => break ERROR_MARKER,
+        }
+    };
+    // Without the root itself, there's nothing to list
+    let (path, depth) = match (path, depth) {
+        (Some(path), Some(depth)) if depth > 0 => (path, depth),
+        _ => return Err(Error::FormatSpecificError(walk_error.to_string())),
+    };
+    let name = path
+        .file_name()
+        .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+    let metadata = std::fs::symlink_metadata(&path).ok();
+    let name = fill_template(&path, name, metadata.as_ref(), template, default)?;
+    Ok(Line {
+        number,
+        depth,
+        name: name + marker,
+    })
+}
+
+fn error_path(walk_error: &ignore::Error) -> Option<PathBuf> {
+    match walk_error {
+        ignore::Error::WithDepth { err, .. } => error_path(err),
+        ignore::Error::WithPath { path, .. } => Some(path.clone()),
+        _ => None,
+    }
+}
+
+fn get_name(
+    entry: &DirEntry,
+    name: String,
+    template: &Template,
+    default: &Option<String>,
+) -> Result<String, Error> {
+    let metadata = entry.metadata().ok();
+    fill_template(entry.path(), name, metadata.as_ref(), template, default)
+}
+
+// Besides `{name}`, templates can use `{path}`, `{kind}` (`file`, `dir`,
+// `symlink`, or `other`), `{size}` (in bytes), `{mode}` (in octal, e.g.
+// `0644`), `{permissions}` (e.g. `rw-r--r--`), `{mtime}`, and `{target}` (what
+// a symlink points to)
+fn fill_template(
+    path: &Path,
+    name: String,
+    metadata: Option<&Metadata>,
+    template: &Template,
+    default: &Option<String>,
+) -> Result<String, Error> {
+    template.fill_with_default(
+        |placeholder_name| match placeholder_name {
+            "name" => Some(name.clone()),
+            "path" => Some(path.to_string_lossy().into_owned()),
+            "kind" => metadata.map(|metadata| kind(metadata).to_string()),
+            "size" => metadata.map(|metadata| metadata.len().to_string()),
+            "mode" => metadata.and_then(mode).map(|mode| format!("{:04o}", mode)),
+            "permissions" => metadata.and_then(mode).map(format_permissions),
+            "mtime" => metadata
+                .and_then(|metadata| metadata.modified().ok())
+                .map(|mtime| humantime::format_rfc3339_seconds(mtime).to_string()),
+            "target" => std::fs::read_link(path)
+                .ok()
+                .map(|target| target.to_string_lossy().into_owned()),
+            _ => None,
+        },
+        default,
+    )
+}
+
+fn kind(metadata: &Metadata) -> &'static str {
+    let file_type = metadata.file_type();
+    if file_type.is_symlink() {
+        "symlink"
+    } else if file_type.is_dir() {
+        "dir"
+    } else if file_type.is_file() {
+        "file"
+    } else {
+        "other"
+    }
+}
+
+#[cfg(unix)]
+fn mode(metadata: &Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(metadata.permissions().mode() & 0o7777)
+}
+
+#[cfg(not(unix))]
+fn mode(_metadata: &Metadata) -> Option<u32> {
+    None
+}
+
+// Formats the permission bits of a Unix mode like `ls -l` does (without the
+// file type)
+pub(crate) fn format_permissions(mode: u32) -> String {
+    let mut permissions = String::new();
+    for shift in [6, 3, 0] {
+        let bits = (mode >> shift) & 0o7;
+        permissions.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        permissions.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        permissions.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+    }
+    permissions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parens;
+    use super::*;
+    use std::fs;
+
+    // A scratch directory that's removed again once the test is done
+    struct TestDir(PathBuf);
+
+    impl TestDir {
+        fn new(test_name: &str, files: &[(&str, &str)]) -> TestDir {
+            let root =
+                std::env::temp_dir().join(format!("ruut-fs-{}-{}", std::process::id(), test_name));
+            let _ = fs::remove_dir_all(&root);
+            fs::create_dir_all(&root).unwrap();
+            for (path, contents) in files {
+                let path = root.join(path);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, contents).unwrap();
+            }
+            TestDir(root)
+        }
+
+        fn scan(&self, template: &str, settings: Settings) -> Result<Node, Error> {
+            let mut root_node = deserialize(
+                self.0.to_string_lossy().into_owned(),
+                template.to_string(),
+                Some("-".to_string()),
+                settings,
+            )?;
+            root_node.name = "root".to_string();
+            Ok(root_node)
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn default_settings() -> Settings {
+        Settings {
+            hidden: false,
+            gitignore: false,
+            follow_symlinks: false,
+        }
+    }
+
+    const FILES: &[(&str, &str)] = &[
+        ("b.txt", "bb"),
+        ("a/2.txt", ""),
+        ("a/1.txt", ""),
+        (".hidden/secret.txt", ""),
+        ("target/debug.log", ""),
+        (".gitignore", "target/\n"),
+    ];
+
+    #[test]
+    fn empty() {
+        let deserialization_err = deserialize(
+            " ".to_string(),
+            "{name}".to_string(),
+            None,
+            default_settings(),
+        )
+        .unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn missing_dir() {
+        let deserialization_err = deserialize(
+            "/definitely/not/a/real/dir".to_string(),
+            "{name}".to_string(),
+            None,
+            default_settings(),
+        )
+        .unwrap_err();
+        let is_format_error = matches!(deserialization_err, Error::FormatSpecificError(_));
+        assert!(is_format_error);
+    }
+
+    #[test]
+    fn skips_hidden() {
+        let dir = TestDir::new("skips_hidden", FILES);
+        let expected =
+            parens::deserialize("root (a (1.txt, 2.txt), b.txt, target (debug.log))".to_string())
+                .unwrap();
+        assert_eq!(dir.scan("{name}", default_settings()).unwrap(), expected);
+    }
+
+    #[test]
+    fn hidden_and_gitignore() {
+        let dir = TestDir::new("hidden_and_gitignore", FILES);
+        let settings = Settings {
+            hidden: true,
+            gitignore: true,
+            follow_symlinks: false,
+        };
+        let expected = parens::deserialize(
+            "root (.gitignore, .hidden (secret.txt), a (1.txt, 2.txt), b.txt)".to_string(),
+        )
+        .unwrap();
+        assert_eq!(dir.scan("{name}", settings).unwrap(), expected);
+    }
+
+    #[test]
+    fn metadata_template() {
+        let dir = TestDir::new("metadata_template", &[("b.txt", "bb")]);
+        let root_node = dir
+            .scan("{name} ({kind}, {size}, {target})", default_settings())
+            .unwrap();
+        assert_eq!(
+            root_node.children,
+            vec![Node::new("b.txt (file, 2, -)".to_string())]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_loop() {
+        let dir = TestDir::new("symlink_loop", &[("a/1.txt", "")]);
+        std::os::unix::fs::symlink("..", dir.0.join("a/up")).unwrap();
+        let expected = parens::deserialize("root (a (1.txt, up))".to_string()).unwrap();
+        assert_eq!(dir.scan("{name}", default_settings()).unwrap(), expected);
+
+        let settings = Settings {
+            follow_symlinks: true,
+            ..default_settings()
+        };
+        let root_node = dir.scan("{name}", settings).unwrap();
+        assert_eq!(
+            root_node.children[0].children[1],
+            Node::new("up [recursive, not followed]".to_string())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn dangling_symlink() {
+        let dir = TestDir::new("dangling_symlink", &[("a/1.txt", "")]);
+        std::os::unix::fs::symlink("nowhere", dir.0.join("a/broken")).unwrap();
+        let settings = Settings {
+            follow_symlinks: true,
+            ..default_settings()
+        };
+        assert_eq!(
+            dir.scan("{name} -> {target}", settings).unwrap(),
+            Node {
+                name: "root".to_string(),
+                children: vec![Node {
+                    name: "a -> -".to_string(),
+                    children: vec![
+                        Node::new("1.txt -> -".to_string()),
+                        Node::new("broken -> nowhere [error reading entry]".to_string()),
+                    ]
+                }]
+            }
+        );
+    }
+
+    #[test]
+    fn permissions() {
+        assert_eq!(format_permissions(0o754), "rwxr-xr--".to_string());
+    }
+}
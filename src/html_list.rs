@@ -0,0 +1,265 @@
+use super::{Error, Node};
+use scraper::{ElementRef, Html, Selector};
+
+const DEFAULT_SELECTOR: &str = "ul, ol";
+// Elements whose text is never shown as part of the item
+const HIDDEN_ELEMENTS: &[&str] = &["script", "style", "template"];
+// Elements that start on a line of their own, so their words can't run into
+// the text around them
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "p",
+    "pre",
+    "section",
+    "summary",
+    "table",
+    "td",
+    "th",
+    "tr",
+];
+
+pub fn deserialize(
+    serialized: String,
+    selector: Option<String>,
+    root_name: Option<String>,
+) -> Result<Node, Error> {
+    if serialized.trim().is_empty() {
+        return Err(Error::EmptyInputError);
+    }
+    let selector_str = selector.unwrap_or_else(|| DEFAULT_SELECTOR.to_string());
+    let selector = Selector::parse(&selector_str).map_err(|selector_error| {
+        Error::FormatSpecificError(format!(
+            "invalid selector `{}`: {}",
+            selector_str, selector_error
+        ))
+    })?;
+    let document = Html::parse_document(&serialized);
+    // The selected element may be a wrapper (e.g. `nav`) around the list
+    let list = document
+        .select(&selector)
+        .find_map(|element| {
+            if is_list(element) {
+                Some(element)
+            } else {
+                element
+                    .descendants()
+                    .filter_map(ElementRef::wrap)
+                    .find(|e| is_list(*e))
+            }
+        })
+        .ok_or_else(|| {
+            Error::FormatSpecificError(format!("no list found matching `{}`", selector_str))
+        })?;
+    let mut items = list_items(list);
+    if items.len() == 1 {
+        Ok(items.pop().unwrap())
+    } else if items.is_empty() {
+        Err(Error::EmptyInputError)
+    } else if let Some(name) = root_name {
+        Ok(Node {
+            name,
+            children: items,
+        })
+    } else {
+        Err(Error::MultipleRootsError)
+    }
+}
+
+fn is_list(element: ElementRef) -> bool {
+    matches!(element.value().name(), "ul" | "ol")
+}
+
+fn list_items(list: ElementRef) -> Vec<Node> {
+    let mut items: Vec<Node> = Vec::new();
+    for child in list.children().filter_map(ElementRef::wrap) {
+        match child.value().name() {
+            "li" => items.push(item_to_node(child)),
+            // A list directly inside another list (instead of inside an `li`)
+            // is a common mistake; it almost always belongs to the item before it
+            "ul" | "ol" => {
+                let mut nested_items = list_items(child);
+                match items.last_mut() {
+                    Some(prev_item) => prev_item.children.append(&mut nested_items),
+                    None => items.append(&mut nested_items),
+                }
+            }
+            // Wrappers like `div`s that sloppy markup puts around items
+            _ => items.append(&mut list_items(child)),
+        }
+    }
+    items
+}
+
+fn item_to_node(item: ElementRef) -> Node {
+    let mut text = String::new();
+    let mut children = Vec::new();
+    collect_item_contents(item, &mut text, &mut children);
+    Node {
+        name: text.split_whitespace().collect::<Vec<_>>().join(" "),
+        children,
+    }
+}
+
+// Gathers an item's own text, leaving the text of any nested lists to their
+// own items
+fn collect_item_contents(element: ElementRef, text: &mut String, children: &mut Vec<Node>) {
+    for child in element.children() {
+        if let Some(text_node) = child.value().as_text() {
+            text.push_str(text_node);
+        } else if let Some(child_element) = ElementRef::wrap(child) {
+            let name = child_element.value().name();
+            if is_list(child_element) {
+                children.append(&mut list_items(child_element));
+            } else if BLOCK_ELEMENTS.contains(&name) {
+                text.push(' ');
+                collect_item_contents(child_element, text, children);
+                text.push(' ');
+            } else if !HIDDEN_ELEMENTS.contains(&name) {
+                // Inline elements like `b` or `code` can sit in the middle of
+                // a word
+                collect_item_contents(child_element, text, children);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parens;
+    use super::*;
+
+    #[test]
+    fn zero_length_html() {
+        let deserialization_err = deserialize("".to_string(), None, None).unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn no_list() {
+        let html = "<p>Nothing to see here</p>";
+        let deserialization_err = deserialize(html.to_string(), None, None).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("no list found matching `ul, ol`".to_string())
+        );
+    }
+
+    #[test]
+    fn invalid_selector() {
+        let html = "<ul><li>Parent</li></ul>";
+        let deserialization_err =
+            deserialize(html.to_string(), Some("ul[".to_string()), None).unwrap_err();
+        let is_format_error = matches!(deserialization_err, Error::FormatSpecificError(_));
+        assert!(is_format_error);
+    }
+
+    #[test]
+    fn multiple_roots() {
+        let html = "<ul><li>Home</li><li>About</li></ul>";
+        let deserialization_err = deserialize(html.to_string(), None, None).unwrap_err();
+        assert_eq!(deserialization_err, Error::MultipleRootsError);
+    }
+
+    #[test]
+    fn multiple_roots_with_root_name() {
+        let html = "<ul><li>Home</li><li>About</li></ul>";
+        let expected = parens::deserialize("Site (Home, About)".to_string()).unwrap();
+        assert_eq!(
+            deserialize(html.to_string(), None, Some("Site".to_string())).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn good_list() {
+        let html = r#"
+            <p>Some text before the list</p>
+            <ul>
+              <li><a href="/parent">Parent</a>
+                <ul>
+                  <li>Child 1
+                  <li><strong>Child</strong> 2
+                    <ol>
+                      <li>Grandchild 1</li>
+                      <li>Grandchild 2</li>
+                    </ol>
+                  </li>
+                  <li>Child 3</li>
+                  <ul>
+                    <li>Grandchild 3</li>
+                  </ul>
+                </ul>
+              </li>
+            </ul>
+        "#;
+        let expected = parens::deserialize(
+            "Parent (Child 1, Child 2 (Grandchild 1, Grandchild 2), Child 3 (Grandchild 3))"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(deserialize(html.to_string(), None, None).unwrap(), expected);
+    }
+
+    #[test]
+    fn inline_and_block_elements() {
+        let html = r#"
+            <ul><li><b>Foo</b>bar
+              <ul>
+                <li><code>main</code>.rs</li>
+                <li><p>First</p><p>Second</p>Third<br>Fourth</li>
+              </ul>
+            </li></ul>
+        "#;
+        let expected =
+            parens::deserialize("Foobar (main.rs, First Second Third Fourth)".to_string()).unwrap();
+        assert_eq!(deserialize(html.to_string(), None, None).unwrap(), expected);
+    }
+
+    #[test]
+    fn skips_scripts_and_styles() {
+        let html =
+            "<ul><li>A<script>var x=1</script><style>li{}</style><template>T</template></li></ul>";
+        assert_eq!(
+            deserialize(html.to_string(), None, None).unwrap(),
+            Node::new("A".to_string())
+        );
+    }
+
+    #[test]
+    fn selected_list() {
+        let html = r#"
+            <ul id="breadcrumbs"><li>Home</li></ul>
+            <nav class="sidebar">
+              <ol><li>Docs<ol><li>Usage</li></ol></li></ol>
+            </nav>
+        "#;
+        let expected = parens::deserialize("Docs (Usage)".to_string()).unwrap();
+        assert_eq!(
+            deserialize(html.to_string(), Some("nav.sidebar".to_string()), None).unwrap(),
+            expected
+        );
+    }
+}
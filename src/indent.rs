@@ -0,0 +1,239 @@
+use super::{Error, Node};
+
+pub(crate) struct Line {
+    pub number: usize,
+    pub depth: usize,
+    pub name: String,
+}
+
+pub fn deserialize(serialized: String) -> Result<Node, Error> {
+    lines_to_node(measure_lines(&serialized)?)
+}
+
+// Builds the tree out of lines that already know how deep they are, so that
+// other line-based formats can share it
+pub(crate) fn lines_to_node(lines: Vec<Line>) -> Result<Node, Error> {
+    let mut stack: Vec<Node> = Vec::new();
+    let mut has_root = false;
+    for line in lines {
+        if line.depth == 0 {
+            if has_root {
+                return Err(Error::MultipleRootsError);
+            }
+            has_root = true;
+        } else if line.depth > stack.len() {
+            return Err(Error::FormatSpecificError(format!(
+                "line {}: unexpected indentation",
+                line.number
+            )));
+        }
+        collapse(&mut stack, line.depth.max(1));
+        stack.push(Node::new(line.name));
+    }
+    collapse(&mut stack, 1);
+    stack.pop().ok_or(Error::EmptyInputError)
+}
+
+// Builds the tree out of `(line number, level, name)` entries for formats
+// where nesting comes from a level (like the number of `#`s of a Markdown
+// heading) rather than from indentation. Skipped levels (e.g. `##` straight to
+// `####`) just nest one deeper. If there's more than one top-level entry,
+// `root_name` becomes the root, standing in for the document itself.
+pub(crate) fn levels_to_node(
+    entries: Vec<(usize, usize, String)>,
+    root_name: Option<String>,
+) -> Result<Node, Error> {
+    // Levels of the entries enclosing the current one
+    let mut open_levels: Vec<usize> = Vec::new();
+    let mut lines = Vec::new();
+    for (number, level, name) in entries {
+        while open_levels.last().is_some_and(|open| *open >= level) {
+            open_levels.pop();
+        }
+        lines.push(Line {
+            number,
+            depth: open_levels.len(),
+            name,
+        });
+        open_levels.push(level);
+    }
+    let root_count = lines.iter().filter(|line| line.depth == 0).count();
+    if let (Some(name), true) = (root_name, root_count > 1) {
+        for line in &mut lines {
+            line.depth += 1;
+        }
+        lines.insert(
+            0,
+            Line {
+                number: 0,
+                depth: 0,
+                name,
+            },
+        );
+    }
+    lines_to_node(lines)
+}
+
+// Pops nodes off the stack and into their parents until only `depth` remain
+fn collapse(stack: &mut Vec<Node>, depth: usize) {
+    while stack.len() > depth {
+        let node = stack.pop().unwrap();
+        stack.last_mut().unwrap().children.push(node);
+    }
+}
+
+fn measure_lines(serialized: &str) -> Result<Vec<Line>, Error> {
+    let mut lines = Vec::new();
+    // Indentation of the first line, which everything else is relative to
+    let mut base: Option<&str> = None;
+    // The whitespace making up one level of indentation, detected from the
+    // first indented line
+    let mut unit: Option<&str> = None;
+    let mut prev_indent_len = 0;
+    for (i, raw_line) in serialized.lines().enumerate() {
+        let number = i + 1;
+        let name = raw_line.trim();
+        if name.is_empty() {
+            continue;
+        }
+        let indent = &raw_line[..raw_line.len() - raw_line.trim_start().len()];
+        let base = *base.get_or_insert(indent);
+        let relative = indent.strip_prefix(base).ok_or_else(|| {
+            Error::FormatSpecificError(format!(
+                "line {}: dedent goes past the indentation of the first line",
+                number
+            ))
+        })?;
+        let depth = if relative.is_empty() {
+            0
+        } else {
+            let unit = *unit.get_or_insert(relative);
+            match depth_of(relative, unit) {
+                Some(depth) => depth,
+                None if relative.len() < prev_indent_len => {
+                    return Err(Error::FormatSpecificError(format!(
+                        "line {}: dedent does not match any outer indentation level",
+                        number
+                    )));
+                }
+                None => {
+                    return Err(Error::FormatSpecificError(format!(
+                        "line {}: indentation does not match the detected indent unit of {}",
+                        number,
+                        describe_unit(unit)
+                    )));
+                }
+            }
+        };
+        prev_indent_len = relative.len();
+        lines.push(Line {
+            number,
+            depth,
+            name: name.to_string(),
+        });
+    }
+    Ok(lines)
+}
+
+fn depth_of(indent: &str, unit: &str) -> Option<usize> {
+    let depth = indent.len() / unit.len();
+    if unit.repeat(depth) == indent {
+        Some(depth)
+    } else {
+        None
+    }
+}
+
+fn describe_unit(unit: &str) -> String {
+    let count = unit.chars().count();
+    let kind = match (unit.contains('\t'), unit.contains(' ')) {
+        (true, false) => "tab",
+        (false, true) => "space",
+        _ => "whitespace character",
+    };
+    format!("{} {}{}", count, kind, if count == 1 { "" } else { "s" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parens;
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let deserialization_err = deserialize("\n  \n".to_string()).unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn multiple_roots() {
+        let outline = "papa\n  bebe\npopo\n  bubu\n";
+        let deserialization_err = deserialize(outline.to_string()).unwrap_err();
+        assert_eq!(deserialization_err, Error::MultipleRootsError);
+    }
+
+    #[test]
+    fn inconsistent_dedent() {
+        let outline = "papa\n    bebe\n        gege\n  fefe\n";
+        let deserialization_err = deserialize(outline.to_string()).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError(
+                "line 4: dedent does not match any outer indentation level".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn skipped_level() {
+        let outline = "papa\n  bebe\n      gege\n";
+        let deserialization_err = deserialize(outline.to_string()).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("line 3: unexpected indentation".to_string())
+        );
+    }
+
+    #[test]
+    fn mixed_tabs_and_spaces() {
+        let outline = "papa\n\tbebe\n  fefe\n";
+        let deserialization_err = deserialize(outline.to_string()).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError(
+                "line 3: indentation does not match the detected indent unit of 1 tab".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn same_as_parens_spaces() {
+        let outline = r#"
+            Parent
+                Child 1
+                Child 2
+
+                    Grandchild 1
+                    Grandchild 2
+                Child 3
+                    Grandchild 3
+        "#;
+        let parens_expr =
+            "Parent (Child 1, Child 2 (Grandchild 1, Grandchild 2), Child 3 (Grandchild 3))";
+        assert_eq!(
+            deserialize(outline.to_string()).unwrap(),
+            parens::deserialize(parens_expr.to_string()).unwrap()
+        );
+    }
+
+    #[test]
+    fn same_as_parens_tabs() {
+        let outline =
+            "Parent\n\tChild 1\n\tChild 2\n\t\tGrandchild 1\n\t\tGrandchild 2\n\tChild 3\n";
+        let parens_expr = "Parent (Child 1, Child 2 (Grandchild 1, Grandchild 2), Child 3)";
+        assert_eq!(
+            deserialize(outline.to_string()).unwrap(),
+            parens::deserialize(parens_expr.to_string()).unwrap()
+        );
+    }
+}
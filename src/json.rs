@@ -1,4 +1,5 @@
-use super::{Error, Node};
+use super::{Error, LeafValue, Node};
+use serde_json::Map;
 use serde_json::Value as JsonValue;
 
 pub fn deserialize(serialized: String) -> Result<Node, Error> {
@@ -36,6 +37,39 @@ fn json_value_to_node(name: String, value: &JsonValue) -> Node {
     }
 }
 
+pub fn serialize(root: &Node, leaf_value: &LeafValue, compact: bool) -> Result<String, Error> {
+    let mut root_map = Map::new();
+    root_map.insert(root.name.clone(), node_to_json_value(root, leaf_value)?);
+    let root_value = JsonValue::Object(root_map);
+    if compact {
+        Ok(root_value.to_string())
+    } else {
+        Ok(serde_json::to_string_pretty(&root_value).unwrap())
+    }
+}
+
+// Converts the node's children into nested objects, keyed by name, which is
+// shared by the formats with the same nested-key shape (e.g. `yaml`)
+pub(crate) fn node_to_json_value(node: &Node, leaf_value: &LeafValue) -> Result<JsonValue, Error> {
+    if node.children.is_empty() {
+        return Ok(match leaf_value {
+            LeafValue::Null => JsonValue::Null,
+            LeafValue::EmptyObject => JsonValue::Object(Map::new()),
+        });
+    }
+    let mut map = Map::new();
+    for child in &node.children {
+        let child_value = node_to_json_value(child, leaf_value)?;
+        if map.insert(child.name.clone(), child_value).is_some() {
+            return Err(Error::FormatSpecificError(format!(
+                "`{}` has more than one child named `{}`, which can't be represented as keys",
+                node.name, child.name
+            )));
+        }
+    }
+    Ok(JsonValue::Object(map))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,6 +156,18 @@ mod tests {
             }
         );
     }
+    #[test]
+    fn document_order() {
+        let root_node = deserialize(r#"{"b": {"z": null, "a": null}}"#.to_string()).unwrap();
+        assert_eq!(
+            root_node,
+            Node {
+                name: "b".to_string(),
+                children: vec![Node::new("z".to_string()), Node::new("a".to_string())]
+            }
+        );
+    }
+
     #[test]
     fn good_json5() {
         let json = r#"
@@ -156,4 +202,54 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn serialize_pretty() {
+        let root_node = Node {
+            name: "cool".to_string(),
+            children: vec![
+                Node {
+                    name: "beans".to_string(),
+                    children: vec![Node::new("man".to_string())],
+                },
+                Node::new("wow".to_string()),
+            ],
+        };
+        let json = serialize(&root_node, &LeafValue::Null, false).unwrap();
+        assert_eq!(
+            json,
+            "{\n  \"cool\": {\n    \"beans\": {\n      \"man\": null\n    },\n    \"wow\": null\n  }\n}"
+        );
+        assert_eq!(deserialize(json).unwrap(), root_node);
+    }
+
+    #[test]
+    fn serialize_compact_with_empty_objects() {
+        let root_node = Node {
+            name: "b".to_string(),
+            children: vec![Node::new("a".to_string()), Node::new("c".to_string())],
+        };
+        let json = serialize(&root_node, &LeafValue::EmptyObject, true).unwrap();
+        assert_eq!(json, r#"{"b":{"a":{},"c":{}}}"#);
+        assert_eq!(deserialize(json).unwrap(), root_node);
+    }
+
+    #[test]
+    fn serialize_duplicate_names() {
+        let root_node = Node {
+            name: "cool".to_string(),
+            children: vec![
+                Node::new("beans".to_string()),
+                Node::new("beans".to_string()),
+            ],
+        };
+        let serialization_err = serialize(&root_node, &LeafValue::Null, false).unwrap_err();
+        assert_eq!(
+            serialization_err,
+            Error::FormatSpecificError(
+                "`cool` has more than one child named `beans`, which can't be represented as keys"
+                    .to_string()
+            )
+        );
+    }
 }
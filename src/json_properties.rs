@@ -1,9 +1,7 @@
-mod template;
-
+use super::template::Template;
 use super::{Error, Node};
 use serde_json::Map;
 use serde_json::Value as JsonValue;
-use template::Template;
 
 pub fn deserialize(
     serialized: String,
@@ -15,6 +13,17 @@ pub fn deserialize(
         return Err(Error::EmptyInputError);
     }
     let root_value: JsonValue = json5::from_str(&serialized)?;
+    value_to_node(root_value, template_str, children_key, default)
+}
+
+// Shared by every property-based format once it has been converted into a
+// `JsonValue` (e.g. `yamlprop`)
+pub(crate) fn value_to_node(
+    root_value: JsonValue,
+    template_str: String,
+    children_key: String,
+    default: Option<String>,
+) -> Result<Node, Error> {
     let template = Template::new(template_str)?;
     match root_value {
         JsonValue::Array(vec) => {
@@ -79,26 +88,64 @@ fn json_value_to_node(
     }
 }
 
+pub fn serialize(
+    root: &Node,
+    name_key: &str,
+    children_key: &str,
+    omit_empty_children: bool,
+    compact: bool,
+) -> Result<String, Error> {
+    if name_key == children_key {
+        return Err(Error::FormatSpecificError(format!(
+            "the name and children keys can't both be `{}`",
+            name_key
+        )));
+    }
+    let root_value = node_to_json_value(root, name_key, children_key, omit_empty_children);
+    if compact {
+        Ok(root_value.to_string())
+    } else {
+        Ok(serde_json::to_string_pretty(&root_value).unwrap())
+    }
+}
+
+fn node_to_json_value(
+    node: &Node,
+    name_key: &str,
+    children_key: &str,
+    omit_empty_children: bool,
+) -> JsonValue {
+    let mut map = Map::new();
+    map.insert(name_key.to_string(), JsonValue::String(node.name.clone()));
+    if !(omit_empty_children && node.children.is_empty()) {
+        let children = node
+            .children
+            .iter()
+            .map(|child| node_to_json_value(child, name_key, children_key, omit_empty_children))
+            .collect();
+        map.insert(children_key.to_string(), JsonValue::Array(children));
+    }
+    JsonValue::Object(map)
+}
+
 fn get_name(
     template: &Template,
     map: &Map<String, JsonValue>,
     default: &Option<String>,
 ) -> Result<String, Error> {
     // Option<&String> -> Option<&JsonValue::Value<String>>
-    template.fill(|placeholder_name| {
-        map.get(placeholder_name)
-            .map(|v| {
+    template.fill_with_default(
+        |placeholder_name| {
+            map.get(placeholder_name).map(|v| {
                 if let JsonValue::String(s) = v {
                     s.to_string()
                 } else {
                     v.to_string()
                 }
             })
-            .or_else(|| default.clone())
-            .ok_or(Error::FormatSpecificError(
-                "missing template value".to_string(),
-            ))
-    })
+        },
+        default,
+    )
 }
 
 #[cfg(test)]
@@ -225,6 +272,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn children_object_in_document_order() {
+        let json = r#"{"name": "b", "children": {"z": {"name": "z"}, "a": {"name": "a"}}}"#;
+        let root_node = deserialize(
+            json.to_string(),
+            "{name}".to_string(),
+            "children".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            root_node,
+            Node {
+                name: "b".to_string(),
+                children: vec![Node::new("z".to_string()), Node::new("a".to_string())]
+            }
+        );
+    }
+
     #[test]
     fn good_json_arr() {
         let json = r#"
@@ -372,4 +438,51 @@ mod tests {
         };
         assert_eq!(root_node, expected_root_node);
     }
+
+    #[test]
+    fn serialize_round_trip() {
+        let root_node = Node {
+            name: "cool".to_string(),
+            children: vec![
+                Node {
+                    name: "beans".to_string(),
+                    children: vec![Node::new("man".to_string())],
+                },
+                Node::new("wow".to_string()),
+            ],
+        };
+        let json = serialize(&root_node, "title", "kids", false, true).unwrap();
+        assert_eq!(
+            json,
+            r#"{"title":"cool","kids":[{"title":"beans","kids":[{"title":"man","kids":[]}]},{"title":"wow","kids":[]}]}"#
+        );
+        let deserialized =
+            deserialize(json, "{title}".to_string(), "kids".to_string(), None).unwrap();
+        assert_eq!(deserialized, root_node);
+    }
+
+    #[test]
+    fn serialize_omitting_empty_children() {
+        let root_node = Node {
+            name: "cool".to_string(),
+            children: vec![Node::new("beans".to_string())],
+        };
+        let json = serialize(&root_node, "name", "children", true, false).unwrap();
+        assert_eq!(
+            json,
+            "{\n  \"name\": \"cool\",\n  \"children\": [\n    {\n      \"name\": \"beans\"\n    }\n  ]\n}"
+        );
+    }
+
+    #[test]
+    fn serialize_same_keys() {
+        let serialization_err =
+            serialize(&Node::new("cool".to_string()), "name", "name", false, false).unwrap_err();
+        assert_eq!(
+            serialization_err,
+            Error::FormatSpecificError(
+                "the name and children keys can't both be `name`".to_string()
+            )
+        );
+    }
 }
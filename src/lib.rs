@@ -1,7 +1,30 @@
+mod archive;
+mod auto;
+mod brace;
+mod csv;
+mod dot;
+mod edges;
+mod fs;
+mod html_list;
+mod indent;
 mod json;
 mod json_properties;
+mod markdown_headings;
+mod markdown_list;
+mod newick;
+mod opml;
+mod org;
 mod parens;
+mod paths;
+mod sexp;
+mod template;
+mod toml;
+mod tree;
+mod xml;
+mod yaml;
+mod yaml_properties;
 
+pub use auto::Detection;
 use std::str::FromStr;
 use std::vec::Vec;
 
@@ -34,6 +57,71 @@ pub enum InputFormat {
     Parens,
     Json,
     JsonProperties,
+    Yaml,
+    YamlProperties,
+    Toml,
+    Indent,
+    Tree,
+    Paths,
+    MarkdownList,
+    MarkdownHeadings,
+    Xml,
+    HtmlList,
+    Csv,
+    Tsv,
+    Dot,
+    Newick,
+    Opml,
+    Org,
+    Fs,
+    Tar,
+    Zip,
+    Sexp,
+    Brace,
+    Auto,
+}
+
+pub enum OutputFormat {
+    Tree,
+    Parens,
+    Json,
+    JsonProperties,
+    Yaml,
+    MarkdownList,
+}
+
+// What leaves are written as in output formats that nest children under keys
+pub enum LeafValue {
+    Null,
+    EmptyObject,
+}
+
+impl FromStr for LeafValue {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "null" => Ok(LeafValue::Null),
+            "{}" => Ok(LeafValue::EmptyObject),
+            _ => Err("invalid leaf value"),
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tree" => Ok(OutputFormat::Tree),
+            "parens" => Ok(OutputFormat::Parens),
+            "json" => Ok(OutputFormat::Json),
+            "jsonprop" => Ok(OutputFormat::JsonProperties),
+            "yaml" => Ok(OutputFormat::Yaml),
+            "markdown-list" => Ok(OutputFormat::MarkdownList),
+            _ => Err("invalid output format type"),
+        }
+    }
 }
 
 impl FromStr for InputFormat {
@@ -44,6 +132,28 @@ impl FromStr for InputFormat {
             "parens" => Ok(InputFormat::Parens),
             "json" => Ok(InputFormat::Json),
             "jsonprop" => Ok(InputFormat::JsonProperties),
+            "yaml" => Ok(InputFormat::Yaml),
+            "yamlprop" => Ok(InputFormat::YamlProperties),
+            "toml" => Ok(InputFormat::Toml),
+            "indent" => Ok(InputFormat::Indent),
+            "tree" => Ok(InputFormat::Tree),
+            "paths" => Ok(InputFormat::Paths),
+            "markdown-list" => Ok(InputFormat::MarkdownList),
+            "markdown-headings" => Ok(InputFormat::MarkdownHeadings),
+            "xml" => Ok(InputFormat::Xml),
+            "html-list" => Ok(InputFormat::HtmlList),
+            "csv" => Ok(InputFormat::Csv),
+            "tsv" => Ok(InputFormat::Tsv),
+            "dot" => Ok(InputFormat::Dot),
+            "newick" => Ok(InputFormat::Newick),
+            "opml" => Ok(InputFormat::Opml),
+            "org" => Ok(InputFormat::Org),
+            "fs" => Ok(InputFormat::Fs),
+            "tar" => Ok(InputFormat::Tar),
+            "zip" => Ok(InputFormat::Zip),
+            "sexp" => Ok(InputFormat::Sexp),
+            "brace" => Ok(InputFormat::Brace),
+            "auto" => Ok(InputFormat::Auto),
             _ => Err("invalid format type"),
         }
     }
@@ -63,19 +173,167 @@ impl From<json5::Error> for Error {
     }
 }
 
+impl From<serde_yaml::Error> for Error {
+    fn from(serde_error: serde_yaml::Error) -> Error {
+        Error::FormatSpecificError(format!("{}", serde_error))
+    }
+}
+
+impl From<::toml::de::Error> for Error {
+    fn from(toml_error: ::toml::de::Error) -> Error {
+        Error::FormatSpecificError(format!("{}", toml_error))
+    }
+}
+
+impl From<::csv::Error> for Error {
+    fn from(csv_error: ::csv::Error) -> Error {
+        Error::FormatSpecificError(format!("{}", csv_error))
+    }
+}
+
+impl From<roxmltree::Error> for Error {
+    fn from(xml_error: roxmltree::Error) -> Error {
+        Error::FormatSpecificError(format!("{}", xml_error))
+    }
+}
+
+// Settings that only apply to some of the formats
+pub struct Options {
+    pub template: String,
+    pub children_key: String,
+    pub default: Option<String>,
+    pub root_name: Option<String>,
+    pub strip_formatting: bool,
+    pub ignore_text: bool,
+    pub ignore_comments: bool,
+    pub selector: Option<String>,
+    pub id_column: Option<String>,
+    pub parent_column: Option<String>,
+    pub label_column: Option<String>,
+    pub hidden: bool,
+    pub gitignore: bool,
+    pub follow_symlinks: bool,
+    pub leaf_value: LeafValue,
+    pub compact: bool,
+    pub name_key: String,
+    pub omit_empty_children: bool,
+    pub indent_width: usize,
+    pub backticks: bool,
+}
+
+// Guesses the format of the input for `auto`, along with the reason why
+pub fn detect_format(serialized: &str, children_key: &str) -> Detection {
+    auto::detect(serialized, children_key)
+}
+
 pub fn prettify(
     serialized: String,
     format: InputFormat,
-    template: String,
-    children_key: String,
-    default: Option<String>,
+    output_format: OutputFormat,
+    options: Options,
 ) -> Result<String, Error> {
+    let format = match format {
+        InputFormat::Auto => detect_format(&serialized, &options.children_key).format,
+        format => format,
+    };
+    let Options {
+        template,
+        children_key,
+        default,
+        root_name,
+        strip_formatting,
+        ignore_text,
+        ignore_comments,
+        selector,
+        id_column,
+        parent_column,
+        label_column,
+        hidden,
+        gitignore,
+        follow_symlinks,
+        leaf_value,
+        compact,
+        name_key,
+        omit_empty_children,
+        indent_width,
+        backticks,
+    } = options;
     let root = match format {
         InputFormat::Parens => parens::deserialize(serialized),
         InputFormat::Json => json::deserialize(serialized),
         InputFormat::JsonProperties => {
-            json_properties::deserialize(serialized, template, children_key, default)
+            json_properties::deserialize(serialized, template, children_key.clone(), default)
+        }
+        InputFormat::Yaml => yaml::deserialize(serialized),
+        InputFormat::YamlProperties => {
+            yaml_properties::deserialize(serialized, template, children_key.clone(), default)
+        }
+        InputFormat::Toml => toml::deserialize(serialized, root_name),
+        InputFormat::Indent => indent::deserialize(serialized),
+        InputFormat::Tree => tree::deserialize(serialized),
+        InputFormat::Paths => paths::deserialize(serialized, root_name),
+        InputFormat::MarkdownList => markdown_list::deserialize(serialized, strip_formatting),
+        InputFormat::MarkdownHeadings => {
+            markdown_headings::deserialize(serialized, root_name, strip_formatting)
         }
+        InputFormat::Xml => {
+            xml::deserialize(serialized, template, default, ignore_text, ignore_comments)
+        }
+        InputFormat::HtmlList => html_list::deserialize(serialized, selector, root_name),
+        InputFormat::Csv | InputFormat::Tsv => csv::deserialize(
+            serialized,
+            if let InputFormat::Tsv = format {
+                b'\t'
+            } else {
+                b','
+            },
+            id_column.unwrap_or_else(|| csv::DEFAULT_ID_COLUMN.to_string()),
+            parent_column.unwrap_or_else(|| csv::DEFAULT_PARENT_COLUMN.to_string()),
+            label_column,
+            template,
+            default,
+        ),
+        InputFormat::Dot => dot::deserialize(serialized),
+        InputFormat::Newick => newick::deserialize(serialized, template, default),
+        InputFormat::Opml => opml::deserialize(serialized, template, default, root_name),
+        InputFormat::Org => org::deserialize(serialized, template, default, root_name),
+        InputFormat::Fs => fs::deserialize(
+            serialized,
+            template,
+            default,
+            fs::Settings {
+                hidden,
+                gitignore,
+                follow_symlinks,
+            },
+        ),
+        InputFormat::Tar | InputFormat::Zip => archive::deserialize(
+            serialized,
+            if let InputFormat::Tar = format {
+                archive::ArchiveFormat::Tar
+            } else {
+                archive::ArchiveFormat::Zip
+            },
+            template,
+            default,
+            root_name,
+        ),
+        InputFormat::Sexp => sexp::deserialize(serialized),
+        InputFormat::Brace => brace::deserialize(serialized, root_name),
+        InputFormat::Auto => unreachable!("`auto` is replaced by the detected format"),
     }?;
-    Ok(render_as_tree::render(&root).join("\n"))
+    match output_format {
+        OutputFormat::Tree => Ok(render_as_tree::render(&root).join("\n")),
+        OutputFormat::Parens => Ok(parens::serialize(&root)),
+        OutputFormat::Json => json::serialize(&root, &leaf_value, compact),
+        OutputFormat::JsonProperties => json_properties::serialize(
+            &root,
+            &name_key,
+            &children_key,
+            omit_empty_children,
+            compact,
+        ),
+        OutputFormat::Yaml => yaml::serialize(&root, &leaf_value),
+        OutputFormat::MarkdownList => markdown_list::serialize(&root, indent_width, backticks),
+    }
 }
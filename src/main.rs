@@ -1,8 +1,45 @@
 use atty::Stream;
-use ruut::{prettify, Error, InputFormat};
+use ruut::{detect_format, prettify, Error, InputFormat, LeafValue, Options, OutputFormat};
 use std::io::{self, Read};
 use structopt::StructOpt;
 
+const INPUT_FORMATS: &[&str] = &[
+    "parens",
+    "json",
+    "jsonprop",
+    "yaml",
+    "yamlprop",
+    "toml",
+    "indent",
+    "tree",
+    "paths",
+    "markdown-list",
+    "markdown-headings",
+    "xml",
+    "html-list",
+    "csv",
+    "tsv",
+    "dot",
+    "newick",
+    "opml",
+    "org",
+    "fs",
+    "tar",
+    "zip",
+    "sexp",
+    "brace",
+    "auto",
+];
+
+const OUTPUT_FORMATS: &[&str] = &[
+    "tree",
+    "parens",
+    "json",
+    "jsonprop",
+    "yaml",
+    "markdown-list",
+];
+
 #[derive(StructOpt)]
 struct Cli {
     serialized_tree: Option<String>,
@@ -10,22 +47,103 @@ struct Cli {
         short,
         long,
         default_value = "parens",
-        raw(possible_values = "&[\"parens\", \"json\", \"jsonprop\"]")
+        raw(possible_values = "INPUT_FORMATS")
     )]
     format: InputFormat,
+    /// The format to print the structure in
+    #[structopt(long, default_value = "tree", raw(possible_values = "OUTPUT_FORMATS"))]
+    to: OutputFormat,
     /// Can be used to customize name of each node, deriving from properties
     /// (e.g. "this boy's id: {id}" will print `this boy's id = 3` if the id of
-    /// the node is 3; only applies to `jsonprop` format)
+    /// the node is 3; only applies to `jsonprop`, `yamlprop`, `xml`, `csv`,
+    /// `tsv`, `newick`, `opml`, `org`, `fs`, `tar`, and `zip` formats)
     #[structopt(short, long, default_value = "{name}")]
     template: String,
     /// The property containing the children of the given node
-    /// (only applies to `jsonprop` format)
+    /// (only applies to `jsonprop` and `yamlprop` formats, and to `jsonprop`
+    /// output format)
     #[structopt(short, long, default_value = "children")]
     children: String,
     /// Raise an error if a property used in the template is missing (only
-    /// applies to `jsonprop` format)
+    /// applies to `jsonprop`, `yamlprop`, `xml`, `csv`, `tsv`, `newick`,
+    /// `opml`, `org`, `fs`, `tar`, and `zip` formats)
     #[structopt(short, long = "raise-on-missing")]
     raise_on_missing_prop: bool,
+    /// The name to give the root node when the input doesn't have a single
+    /// named root (only applies to `toml`, `paths`, `markdown-headings`,
+    /// `html-list`, `opml`, `org`, `tar`, `zip`, and `brace` formats)
+    #[structopt(long = "root-name")]
+    root_name: Option<String>,
+    /// Strip inline formatting (emphasis, code spans, links) from names (only
+    /// applies to `markdown-list` and `markdown-headings` formats)
+    #[structopt(long = "strip-formatting")]
+    strip_formatting: bool,
+    /// Leave out text content (only applies to `xml` format)
+    #[structopt(long = "ignore-text")]
+    ignore_text: bool,
+    /// Leave out comments (only applies to `xml` format)
+    #[structopt(long = "ignore-comments")]
+    ignore_comments: bool,
+    /// CSS selector for the list to use, or an element containing it
+    /// (defaults to the first list; only applies to `html-list` format)
+    #[structopt(long)]
+    selector: Option<String>,
+    /// The column uniquely identifying each row (defaults to `id`; only
+    /// applies to `csv` and `tsv` formats)
+    #[structopt(long = "id-column")]
+    id_column: Option<String>,
+    /// The column containing the id of each row's parent, which is empty for
+    /// the root (defaults to `parent_id`; only applies to `csv` and `tsv`
+    /// formats)
+    #[structopt(long = "parent-column")]
+    parent_column: Option<String>,
+    /// The column to use as the name of each node, instead of filling in the
+    /// template (defaults to `label` if there is one but no `name` column and
+    /// no template is given; only applies to `csv` and `tsv` formats)
+    #[structopt(long = "label-column")]
+    label_column: Option<String>,
+    /// Include hidden files and directories (only applies to `fs` format)
+    #[structopt(long)]
+    hidden: bool,
+    /// Leave out files ignored by `.gitignore` (only applies to `fs` format)
+    #[structopt(long)]
+    gitignore: bool,
+    /// Descend into symlinked directories, listing (but not following) any
+    /// that loop back on themselves (only applies to `fs` format)
+    #[structopt(long = "follow-symlinks")]
+    follow_symlinks: bool,
+    /// Print which format was detected and why to stderr (only applies to
+    /// `auto` format)
+    #[structopt(long)]
+    explain: bool,
+    /// What to write leaves as (only applies to `json` and `yaml` output
+    /// formats)
+    #[structopt(
+        long = "leaf-value",
+        default_value = "null",
+        raw(possible_values = r#"&["null", "{}"]"#)
+    )]
+    leaf_value: LeafValue,
+    /// Print everything on one line instead of pretty-printing it (only
+    /// applies to `json` and `jsonprop` output formats)
+    #[structopt(long)]
+    compact: bool,
+    /// The property to put the name of each node in (only applies to
+    /// `jsonprop` output format)
+    #[structopt(long = "name-key", default_value = "name")]
+    name_key: String,
+    /// Leave out the children property of nodes without children (only
+    /// applies to `jsonprop` output format)
+    #[structopt(long = "omit-empty-children")]
+    omit_empty_children: bool,
+    /// How many spaces to indent each level of the list by, from 2 to 5 (only
+    /// applies to `markdown-list` output format)
+    #[structopt(long = "indent-width", default_value = "2")]
+    indent_width: usize,
+    /// Wrap each name in backticks instead of escaping it (only applies to
+    /// `markdown-list` output format)
+    #[structopt(long)]
+    backticks: bool,
 }
 
 fn main() {
@@ -49,7 +167,42 @@ fn main() {
     };
 
     if let Some(st) = serialized_tree {
-        match prettify(st, args.format, args.template, args.children, default) {
+        let format = match args.format {
+            InputFormat::Auto => {
+                let detection = detect_format(&st, &args.children);
+                if args.explain {
+                    eprintln!(
+                        "Detected format `{}`: {}",
+                        detection.format_name, detection.reason
+                    );
+                }
+                detection.format
+            }
+            format => format,
+        };
+        let options = Options {
+            template: args.template,
+            children_key: args.children,
+            default,
+            root_name: args.root_name,
+            strip_formatting: args.strip_formatting,
+            ignore_text: args.ignore_text,
+            ignore_comments: args.ignore_comments,
+            selector: args.selector,
+            id_column: args.id_column,
+            parent_column: args.parent_column,
+            label_column: args.label_column,
+            hidden: args.hidden,
+            gitignore: args.gitignore,
+            follow_symlinks: args.follow_symlinks,
+            leaf_value: args.leaf_value,
+            compact: args.compact,
+            name_key: args.name_key,
+            omit_empty_children: args.omit_empty_children,
+            indent_width: args.indent_width,
+            backticks: args.backticks,
+        };
+        match prettify(st, format, args.to, options) {
             Ok(prettified) => println!("{}", prettified),
             Err(Error::EmptyInputError) => {
                 eprintln!("Error: empty input -- structure must be passed as the first argument or via stdin");
@@ -0,0 +1,189 @@
+use super::indent;
+use super::markdown_list::strip_inline_formatting;
+use super::{Error, Node};
+
+const MAX_LEVEL: usize = 6;
+const MAX_INDENT: usize = 3;
+
+struct Heading {
+    number: usize,
+    level: usize,
+    text: String,
+}
+
+pub fn deserialize(
+    serialized: String,
+    root_name: Option<String>,
+    strip_formatting: bool,
+) -> Result<Node, Error> {
+    let entries = find_headings(&serialized)
+        .into_iter()
+        .map(|heading| {
+            let name = if strip_formatting {
+                strip_inline_formatting(&heading.text)
+            } else {
+                heading.text
+            };
+            (heading.number, heading.level, name)
+        })
+        .collect();
+    // Without a single H1 to use, the document itself becomes the root
+    indent::levels_to_node(entries, root_name)
+}
+
+fn find_headings(serialized: &str) -> Vec<Heading> {
+    let mut headings = Vec::new();
+    let mut fence: Option<&str> = None;
+    // The paragraph a setext underline (`===`/`---`) would turn into a heading
+    let mut paragraph: Vec<&str> = Vec::new();
+    for (i, raw_line) in serialized.lines().enumerate() {
+        let number = i + 1;
+        let indent = raw_line.len() - raw_line.trim_start_matches(' ').len();
+        let trimmed = raw_line.trim();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if indent > MAX_INDENT || raw_line.starts_with('\t') {
+            // Indented code block (or a paragraph continuation)
+            if !paragraph.is_empty() {
+                paragraph.push(trimmed);
+            }
+            continue;
+        }
+        if let Some(marker) = ["```", "~~~"].iter().find(|m| trimmed.starts_with(*m)) {
+            fence = Some(marker);
+            paragraph.clear();
+        } else if let Some((level, text)) = parse_atx(trimmed) {
+            headings.push(Heading {
+                number,
+                level,
+                text,
+            });
+            paragraph.clear();
+        } else if let (Some(level), false) = (setext_level(trimmed), paragraph.is_empty()) {
+            headings.push(Heading {
+                number: number - paragraph.len(),
+                level,
+                text: paragraph.join(" "),
+            });
+            paragraph.clear();
+        } else if trimmed.is_empty() {
+            paragraph.clear();
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+    headings
+}
+
+fn parse_atx(line: &str) -> Option<(usize, String)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > MAX_LEVEL {
+        return None;
+    }
+    let rest = &line[level..];
+    if !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    let rest = rest.trim();
+    // An optional closing sequence of `#`s has to be separated by whitespace
+    let without_closing = rest.trim_end_matches('#');
+    let text = if without_closing.is_empty() || without_closing.ends_with([' ', '\t']) {
+        without_closing.trim_end()
+    } else {
+        rest
+    };
+    Some((level, text.to_string()))
+}
+
+fn setext_level(line: &str) -> Option<usize> {
+    if !line.is_empty() && line.chars().all(|c| c == '=') {
+        Some(1)
+    } else if !line.is_empty() && line.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parens;
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let markdown = "Just some prose.\n\n    # not a heading\n";
+        let deserialization_err = deserialize(markdown.to_string(), None, false).unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn multiple_roots() {
+        let markdown = "## Intro\n\n## Usage\n";
+        let deserialization_err = deserialize(markdown.to_string(), None, false).unwrap_err();
+        assert_eq!(deserialization_err, Error::MultipleRootsError);
+    }
+
+    #[test]
+    fn multiple_roots_with_root_name() {
+        let markdown = "## Intro\n\n## Usage\n### CLI\n";
+        let expected = parens::deserialize("README.md (Intro, Usage (CLI))".to_string()).unwrap();
+        assert_eq!(
+            deserialize(markdown.to_string(), Some("README.md".to_string()), false).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn good_headings() {
+        let markdown = r#"
+Parent
+======
+
+Some intro text.
+
+## Child 1 ##
+
+Child 2
+-------
+
+#### Grandchild 1
+
+```markdown
+# Not a heading
+```
+
+### Grandchild 2
+
+---
+
+## Child 3
+### Grandchild 3 #hashtag
+"#;
+        let expected = parens::deserialize(
+            "Parent (Child 1, Child 2 (Grandchild 1, Grandchild 2), Child 3 (Grandchild 3 #hashtag))"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            deserialize(markdown.to_string(), Some("ignored.md".to_string()), false).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn strips_formatting() {
+        let markdown = "# The `ruut` *README*\n## Usage\n";
+        assert_eq!(
+            deserialize(markdown.to_string(), None, true).unwrap(),
+            Node {
+                name: "The ruut README".to_string(),
+                children: vec![Node::new("Usage".to_string())]
+            }
+        );
+    }
+}
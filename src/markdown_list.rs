@@ -0,0 +1,472 @@
+use super::indent::{self, Line};
+use super::{Error, Node};
+
+const TAB_WIDTH: usize = 4;
+const MIN_INDENT_WIDTH: usize = 2;
+// An item's text starts 2 columns in (after the `- `), and anything indented 4
+// or more past that is a code block instead of a nested list
+const MAX_INDENT_WIDTH: usize = 5;
+// Characters that could start inline formatting (or an HTML tag or entity)
+const SPECIAL_CHARS: &[char] = &['\\', '`', '*', '_', '~', '[', ']', '<', '>', '&'];
+
+struct Item<'a> {
+    column: usize,
+    text: &'a str,
+}
+
+pub fn deserialize(serialized: String, strip_formatting: bool) -> Result<Node, Error> {
+    let mut lines = Vec::new();
+    // Columns of the markers of the items enclosing the current one
+    let mut open_columns: Vec<usize> = Vec::new();
+    let mut fence: Option<&str> = None;
+    for (i, raw_line) in serialized.lines().enumerate() {
+        let trimmed = raw_line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(marker) = ["```", "~~~"].iter().find(|m| trimmed.starts_with(*m)) {
+            fence = Some(marker);
+            continue;
+        }
+        // Anything that isn't a list item is surrounding prose
+        let item = match parse_item(raw_line) {
+            Some(item) => item,
+            None => continue,
+        };
+        while open_columns.last().is_some_and(|col| *col >= item.column) {
+            open_columns.pop();
+        }
+        let name = if strip_formatting {
+            strip_inline_formatting(item.text)
+        } else {
+            item.text.to_string()
+        };
+        lines.push(Line {
+            number: i + 1,
+            depth: open_columns.len(),
+            name,
+        });
+        open_columns.push(item.column);
+    }
+    indent::lines_to_node(lines)
+}
+
+fn parse_item(line: &str) -> Option<Item<'_>> {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    let column = indent
+        .chars()
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum();
+    let after_marker = if let Some(rest) = trimmed.strip_prefix(['-', '*', '+']) {
+        rest
+    } else {
+        let digits = trimmed.len()
+            - trimmed
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+        if digits == 0 {
+            return None;
+        }
+        trimmed[digits..].strip_prefix(['.', ')'])?
+    };
+    // Markers must be followed by whitespace (`-foo` and `**bold**` are prose),
+    // unless the item is empty
+    if !(after_marker.is_empty() || after_marker.starts_with([' ', '\t'])) {
+        return None;
+    }
+    Some(Item {
+        column,
+        text: after_marker.trim(),
+    })
+}
+
+// Removes emphasis, strikethrough, code span backticks, link/image syntax, and
+// escapes (including numeric character references like `&#32;`), leaving only
+// the text that would be displayed
+pub(crate) fn strip_inline_formatting(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut stripped = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '`' => {
+                // Code spans are copied verbatim, minus the backticks
+                let ticks = chars[i..].iter().take_while(|c| **c == '`').count();
+                let content_start = i + ticks;
+                let closing = (content_start..chars.len())
+                    .find(|j| chars[*j..].iter().take_while(|c| **c == '`').count() == ticks);
+                match closing {
+                    Some(closing) => {
+                        let mut content = &chars[content_start..closing];
+                        // A single space on both sides is padding, which lets
+                        // code spans start or end with a backtick
+                        if content.len() > 2
+                            && content[0] == ' '
+                            && content[content.len() - 1] == ' '
+                            && content.iter().any(|c| *c != ' ')
+                        {
+                            content = &content[1..content.len() - 1];
+                        }
+                        stripped.extend(content);
+                        i = closing + ticks;
+                    }
+                    None => {
+                        stripped.extend(&chars[i..content_start]);
+                        i = content_start;
+                    }
+                }
+            }
+            '\\' if chars.get(i + 1).is_some_and(|c| c.is_ascii_punctuation()) => {
+                stripped.push(chars[i + 1]);
+                i += 2;
+            }
+            '&' => match numeric_char_reference(&chars, i) {
+                Some((c, reference_end)) => {
+                    stripped.push(c);
+                    i = reference_end + 1;
+                }
+                None => {
+                    stripped.push('&');
+                    i += 1;
+                }
+            },
+            '!' if chars.get(i + 1) == Some(&'[') => i += 1,
+            '[' => match link_text_end(&chars, i) {
+                Some((text_end, link_end)) => {
+                    stripped.push_str(&strip_inline_formatting(
+                        &chars[i + 1..text_end].iter().collect::<String>(),
+                    ));
+                    i = link_end + 1;
+                }
+                None => {
+                    stripped.push('[');
+                    i += 1;
+                }
+            },
+            '*' | '_' | '~' => {
+                let prev = i.checked_sub(1).map(|j| chars[j]);
+                let run = chars[i..].iter().take_while(|c| **c == chars[i]).count();
+                let next = chars.get(i + run);
+                let is_word_char = |c: Option<&char>| c.is_some_and(|c| c.is_alphanumeric());
+                // Only markers at the edge of a word are formatting, so
+                // `snake_case_name` is left alone
+                if (chars[i] == '~' && run != 2)
+                    || (is_word_char(prev.as_ref()) && is_word_char(next))
+                {
+                    stripped.extend(&chars[i..i + run]);
+                }
+                i += run;
+            }
+            c => {
+                stripped.push(c);
+                i += 1;
+            }
+        }
+    }
+    stripped
+}
+
+// For a `&#decimal;` or `&#xhex;` reference starting at `start`, returns the
+// character it stands for and the index of its `;`
+fn numeric_char_reference(chars: &[char], start: usize) -> Option<(char, usize)> {
+    let mut i = start + 1;
+    if chars.get(i) != Some(&'#') {
+        return None;
+    }
+    i += 1;
+    let radix = if matches!(chars.get(i), Some('x') | Some('X')) {
+        i += 1;
+        16
+    } else {
+        10
+    };
+    let digits_start = i;
+    while chars.get(i).is_some_and(|c| c.is_digit(radix)) {
+        i += 1;
+    }
+    if i == digits_start || chars.get(i) != Some(&';') {
+        return None;
+    }
+    let digits: String = chars[digits_start..i].iter().collect();
+    let c = u32::from_str_radix(&digits, radix)
+        .ok()
+        .and_then(char::from_u32)?;
+    Some((c, i))
+}
+
+pub fn serialize(root: &Node, indent_width: usize, backticks: bool) -> Result<String, Error> {
+    // Anything less isn't enough for a nested list to belong to the item above
+    if indent_width < MIN_INDENT_WIDTH {
+        return Err(Error::FormatSpecificError(format!(
+            "indent width must be at least {}",
+            MIN_INDENT_WIDTH
+        )));
+    }
+    if indent_width > MAX_INDENT_WIDTH {
+        return Err(Error::FormatSpecificError(format!(
+            "indent width must be at most {}",
+            MAX_INDENT_WIDTH
+        )));
+    }
+    let mut lines = Vec::new();
+    push_items(root, 0, indent_width, backticks, &mut lines);
+    Ok(lines.join("\n"))
+}
+
+fn push_items(
+    node: &Node,
+    depth: usize,
+    indent_width: usize,
+    backticks: bool,
+    lines: &mut Vec<String>,
+) {
+    let name = if backticks {
+        // A line break would end the item, and can't be escaped in a code span
+        code_span(&node.name.replace("\r\n", " ").replace('\n', " "))
+    } else {
+        escape(&node.name)
+    };
+    lines.push(format!("{}- {}", " ".repeat(depth * indent_width), name));
+    for child in &node.children {
+        push_items(child, depth + 1, indent_width, backticks, lines);
+    }
+}
+
+fn escape(name: &str) -> String {
+    // The item's text is trimmed when it's read
+    let text_start = name.len() - name.trim_start().len();
+    let text_end = name.trim_end().len();
+    let mut escaped = String::new();
+    for (i, c) in name.char_indices() {
+        if matches!(c, '\n' | '\r') || (c.is_whitespace() && (i < text_start || i >= text_end)) {
+            // So are line breaks and surrounding whitespace, which are written
+            // as character references instead
+            escaped.push_str(&format!("&#{};", c as u32));
+            continue;
+        }
+        if SPECIAL_CHARS.contains(&c) {
+            escaped.push('\\');
+        } else if i == 0 && matches!(c, '#' | '-' | '+') {
+            // These would turn the item's text into a heading or another list
+            escaped.push('\\');
+        } else if matches!(c, '.' | ')') && i > 0 && name[..i].chars().all(|c| c.is_ascii_digit()) {
+            // As would an ordered list marker like `1.`
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// Uses more backticks than the longest run of them in the name, so that the
+// name can't close the code span early
+fn code_span(name: &str) -> String {
+    if name.is_empty() {
+        return String::new();
+    }
+    let longest_run = name
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    let fence = "`".repeat(longest_run + 1);
+    // One space of padding on both sides is dropped when the span is read, so
+    // it's added whenever the name would otherwise lose characters
+    let is_padded = name.starts_with(' ') && name.ends_with(' ') && name.contains(|c| c != ' ');
+    if name.starts_with('`') || name.ends_with('`') || is_padded {
+        format!("{} {} {}", fence, name, fence)
+    } else {
+        format!("{}{}{}", fence, name, fence)
+    }
+}
+
+// For a `[text](url)` starting at `start`, finds the index of the `]` and of
+// the `)`
+fn link_text_end(chars: &[char], start: usize) -> Option<(usize, usize)> {
+    let text_end = (start + 1..chars.len()).find(|j| chars[*j] == ']')?;
+    if chars.get(text_end + 1) != Some(&'(') {
+        return None;
+    }
+    let link_end = (text_end + 2..chars.len()).find(|j| chars[*j] == ')')?;
+    Some((text_end, link_end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parens;
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let markdown = "# Just a heading\n\nSome prose.\n";
+        let deserialization_err = deserialize(markdown.to_string(), false).unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn multiple_roots() {
+        let markdown = "- papa\n  - bebe\n- popo\n  - bubu\n";
+        let deserialization_err = deserialize(markdown.to_string(), false).unwrap_err();
+        assert_eq!(deserialization_err, Error::MultipleRootsError);
+    }
+
+    #[test]
+    fn empty_items() {
+        let markdown = "- root\n  - \n    - child\n  -\n";
+        assert_eq!(
+            deserialize(markdown.to_string(), false).unwrap(),
+            Node {
+                name: "root".to_string(),
+                children: vec![
+                    Node {
+                        name: "".to_string(),
+                        children: vec![Node::new("child".to_string())]
+                    },
+                    Node::new("".to_string()),
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn good_list() {
+        let markdown = r#"
+Here's how things are laid out:
+
+* Parent
+  - Child 1
+  - Child 2
+      1. Grandchild 1
+      2) Grandchild 2
+  + Child 3
+	- Grandchild 3
+
+```
+- not an item
+```
+
+That's it!
+"#;
+        let expected = parens::deserialize(
+            "Parent (Child 1, Child 2 (Grandchild 1, Grandchild 2), Child 3 (Grandchild 3))"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(deserialize(markdown.to_string(), false).unwrap(), expected);
+    }
+
+    #[test]
+    fn keeps_formatting_by_default() {
+        let markdown = "- **Parent**\n  - `lib.rs`\n";
+        assert_eq!(
+            deserialize(markdown.to_string(), false).unwrap(),
+            Node {
+                name: "**Parent**".to_string(),
+                children: vec![Node::new("`lib.rs`".to_string())]
+            }
+        );
+    }
+
+    #[test]
+    fn strips_formatting() {
+        let markdown = r#"
+- **Parent** _folder_
+  - `__init__.py`
+  - [Child *2*](https://example.com/child_2)
+  - ~~old~~ snake_case_name
+  - ![logo](logo.png)
+"#;
+        assert_eq!(
+            deserialize(markdown.to_string(), true).unwrap(),
+            Node {
+                name: "Parent folder".to_string(),
+                children: vec![
+                    Node::new("__init__.py".to_string()),
+                    Node::new("Child 2".to_string()),
+                    Node::new("old snake_case_name".to_string()),
+                    Node::new("logo".to_string()),
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn serialize_nested() {
+        let root_node = parens::deserialize(
+            "Parent (Child 1, Child 2 (Grandchild 1, Grandchild 2), Child 3)".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            serialize(&root_node, 4, false).unwrap(),
+            "- Parent\n    - Child 1\n    - Child 2\n        - Grandchild 1\n        - Grandchild 2\n    - Child 3"
+        );
+        assert_eq!(
+            serialize(&root_node, 1, false).unwrap_err(),
+            Error::FormatSpecificError("indent width must be at least 2".to_string())
+        );
+        assert!(serialize(&root_node, 5, false).is_ok());
+        assert_eq!(
+            serialize(&root_node, 6, false).unwrap_err(),
+            Error::FormatSpecificError("indent width must be at most 5".to_string())
+        );
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        let names = [
+            "**bold** _snake_case_",
+            "[link](url) <tag> &amp; ~~old~~",
+            "# heading",
+            "- list",
+            "1. ordered",
+            "C:\\Users\\me",
+            "`code` and ``more``",
+            "",
+            "   ",
+            "  padded\t",
+            " padded on both sides ",
+            "&#32; not a reference",
+        ];
+        let root_node = Node {
+            name: "Parent".to_string(),
+            children: names
+                .iter()
+                .map(|name| Node::new(name.to_string()))
+                .collect(),
+        };
+        for backticks in [false, true] {
+            let markdown = serialize(&root_node, 2, backticks).unwrap();
+            assert_eq!(deserialize(markdown, true).unwrap(), root_node);
+        }
+
+        let root_node = Node {
+            name: "".to_string(),
+            children: vec![
+                Node {
+                    name: "".to_string(),
+                    children: vec![Node::new("A".to_string()), Node::new("B".to_string())],
+                },
+                Node::new("two\nlines".to_string()),
+            ],
+        };
+        let markdown = serialize(&root_node, 2, false).unwrap();
+        assert_eq!(markdown, "- \n  - \n    - A\n    - B\n  - two&#10;lines");
+        assert_eq!(deserialize(markdown, true).unwrap(), root_node);
+    }
+
+    #[test]
+    fn serialize_backticks() {
+        let root_node = Node {
+            name: "lib.rs".to_string(),
+            children: vec![Node::new("`main`".to_string())],
+        };
+        assert_eq!(
+            serialize(&root_node, 2, true).unwrap(),
+            "- `lib.rs`\n  - `` `main` ``"
+        );
+    }
+}
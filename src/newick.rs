@@ -0,0 +1,270 @@
+use super::template::Template;
+use super::{Error, Node};
+
+const DELIMITERS: &[char] = &['(', ')', ',', ':', ';', '[', ']', '\''];
+
+pub fn deserialize(
+    serialized: String,
+    template_str: String,
+    default: Option<String>,
+) -> Result<Node, Error> {
+    let template = Template::new(template_str)?;
+    let mut parser = Parser {
+        chars: serialized.chars().collect(),
+        pos: 0,
+        template: &template,
+        default: &default,
+    };
+    parser.skip_insignificant()?;
+    if parser.peek().is_none() {
+        return Err(Error::EmptyInputError);
+    }
+    let root = parser.parse_subtree()?;
+    if parser.peek() == Some(';') {
+        parser.pos += 1;
+        parser.skip_insignificant()?;
+    }
+    match parser.peek() {
+        None => Ok(root),
+        // Another tree (or another node with no parentheses around the two)
+        Some(_) => Err(Error::MultipleRootsError),
+    }
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    template: &'a Template,
+    default: &'a Option<String>,
+}
+
+impl Parser<'_> {
+    fn parse_subtree(&mut self) -> Result<Node, Error> {
+        let mut children = Vec::new();
+        if self.peek() == Some('(') {
+            self.pos += 1;
+            loop {
+                self.skip_insignificant()?;
+                children.push(self.parse_subtree()?);
+                match self.peek() {
+                    Some(',') => self.pos += 1,
+                    Some(')') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(self.unexpected("`,` or `)`")),
+                }
+            }
+            self.skip_insignificant()?;
+        }
+        let label = self.parse_label()?;
+        let length = if self.peek() == Some(':') {
+            self.pos += 1;
+            self.skip_insignificant()?;
+            Some(self.parse_length()?)
+        } else {
+            None
+        };
+        let name = self.template.fill_with_default(
+            |placeholder_name| match placeholder_name {
+                "name" => Some(label.clone()),
+                "length" => length.clone(),
+                _ => None,
+            },
+            self.default,
+        )?;
+        Ok(Node { name, children })
+    }
+
+    // Unlabeled nodes (common for internal ones) just get an empty label
+    fn parse_label(&mut self) -> Result<String, Error> {
+        let label = if self.peek() == Some('\'') {
+            self.pos += 1;
+            let mut label = String::new();
+            loop {
+                match self.next() {
+                    // Quotes inside quoted labels are escaped by doubling them
+                    Some('\'') if self.peek() == Some('\'') => {
+                        self.pos += 1;
+                        label.push('\'');
+                    }
+                    Some('\'') => break,
+                    Some(c) => label.push(c),
+                    None => {
+                        return Err(Error::FormatSpecificError(
+                            "quoted label missing closing `'`".to_string(),
+                        ));
+                    }
+                }
+            }
+            label
+        } else {
+            // Underscores in unquoted labels stand in for spaces
+            self.take_until_delimiter().trim().replace('_', " ")
+        };
+        self.skip_insignificant()?;
+        Ok(label)
+    }
+
+    fn parse_length(&mut self) -> Result<String, Error> {
+        let length = self.take_until_delimiter().trim().to_string();
+        if length.parse::<f64>().is_err() {
+            return Err(Error::FormatSpecificError(format!(
+                "invalid branch length `{}`",
+                length
+            )));
+        }
+        self.skip_insignificant()?;
+        Ok(length)
+    }
+
+    fn take_until_delimiter(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| !DELIMITERS.contains(&c)) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    // Skips whitespace and `[...]` comments
+    fn skip_insignificant(&mut self) -> Result<(), Error> {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => self.pos += 1,
+                Some('[') => {
+                    while self.next() != Some(']') {
+                        if self.peek().is_none() {
+                            return Err(Error::FormatSpecificError(
+                                "comment missing closing `]`".to_string(),
+                            ));
+                        }
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> Error {
+        match self.peek() {
+            Some(c) => {
+                Error::FormatSpecificError(format!("expected {} but found `{}`", expected, c))
+            }
+            None => Error::FormatSpecificError(format!(
+                "expected {} but reached the end of the input",
+                expected
+            )),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parens;
+    use super::*;
+
+    fn deserialize_newick(newick: &str, template: &str) -> Result<Node, Error> {
+        deserialize(
+            newick.to_string(),
+            template.to_string(),
+            Some("<missing>".to_string()),
+        )
+    }
+
+    #[test]
+    fn empty() {
+        let deserialization_err = deserialize_newick(" [just a comment] ", "{name}").unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn multiple_roots() {
+        let deserialization_err = deserialize_newick("(A,B)C;(D,E)F;", "{name}").unwrap_err();
+        assert_eq!(deserialization_err, Error::MultipleRootsError);
+    }
+
+    #[test]
+    fn unbalanced() {
+        let deserialization_err = deserialize_newick("(A,(B,C)D;", "{name}").unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("expected `,` or `)` but found `;`".to_string())
+        );
+    }
+
+    #[test]
+    fn invalid_length() {
+        let deserialization_err = deserialize_newick("(A:abc,B)C;", "{name}").unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("invalid branch length `abc`".to_string())
+        );
+    }
+
+    #[test]
+    fn good_newick() {
+        let newick = "(A:0.1,B:0.2,(C,D)E)F;";
+        let expected = parens::deserialize("F (A, B, E (C, D))".to_string()).unwrap();
+        assert_eq!(deserialize_newick(newick, "{name}").unwrap(), expected);
+    }
+
+    #[test]
+    fn unlabeled_nodes() {
+        let root_node =
+            deserialize("(A,B,(C,D));".to_string(), "{name}".to_string(), None).unwrap();
+        assert_eq!(
+            root_node,
+            Node {
+                name: "".to_string(),
+                children: vec![
+                    Node::new("A".to_string()),
+                    Node::new("B".to_string()),
+                    Node {
+                        name: "".to_string(),
+                        children: vec![Node::new("C".to_string()), Node::new("D".to_string())]
+                    },
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn quoted_labels_and_comments() {
+        let newick = "('Child 1'[&support=90], 'Child''s 2' , Child_3)Parent ;\n";
+        let expected =
+            parens::deserialize("Parent (Child 1, Child's 2, Child 3)".to_string()).unwrap();
+        assert_eq!(deserialize_newick(newick, "{name}").unwrap(), expected);
+    }
+
+    #[test]
+    fn length_template() {
+        let newick = "(A:0.1,:2e-3,(C,D)E:1)F;";
+        assert_eq!(
+            deserialize_newick(newick, "{name} ({length})").unwrap(),
+            Node {
+                name: "F (<missing>)".to_string(),
+                children: vec![
+                    Node::new("A (0.1)".to_string()),
+                    Node::new(" (2e-3)".to_string()),
+                    Node {
+                        name: "E (1)".to_string(),
+                        children: vec![
+                            Node::new("C (<missing>)".to_string()),
+                            Node::new("D (<missing>)".to_string()),
+                        ]
+                    },
+                ]
+            }
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use super::template::Template;
+use super::{Error, Node};
+use roxmltree::Document;
+
+type XmlNode<'a, 'input> = roxmltree::Node<'a, 'input>;
+
+pub fn deserialize(
+    serialized: String,
+    template_str: String,
+    default: Option<String>,
+    root_name: Option<String>,
+) -> Result<Node, Error> {
+    if serialized.trim().is_empty() {
+        return Err(Error::EmptyInputError);
+    }
+    let document = Document::parse(&serialized)?;
+    let template = Template::new(template_str)?;
+    let opml = document.root_element();
+    if !opml.has_tag_name("opml") {
+        return Err(Error::FormatSpecificError(
+            "root element must be `<opml>`".to_string(),
+        ));
+    }
+    let body = child_element(opml, "body")
+        .ok_or_else(|| Error::FormatSpecificError("missing `<body>`".to_string()))?;
+    let mut outlines = outlines_to_nodes(body, &template, &default)?;
+    if outlines.len() == 1 {
+        return Ok(outlines.pop().unwrap());
+    } else if outlines.is_empty() {
+        return Err(Error::EmptyInputError);
+    }
+    // Several top-level outlines are common, so fall back on the document's
+    // own title to hold them
+    let title = child_element(opml, "head")
+        .and_then(|head| child_element(head, "title"))
+        .and_then(|title| title.text())
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+        .map(str::to_string);
+    match root_name.or(title) {
+        Some(name) => Ok(Node {
+            name,
+            children: outlines,
+        }),
+        None => Err(Error::MultipleRootsError),
+    }
+}
+
+fn child_element<'a, 'input>(
+    parent: XmlNode<'a, 'input>,
+    tag: &str,
+) -> Option<XmlNode<'a, 'input>> {
+    parent.children().find(|child| child.has_tag_name(tag))
+}
+
+fn outlines_to_nodes(
+    parent: XmlNode,
+    template: &Template,
+    default: &Option<String>,
+) -> Result<Vec<Node>, Error> {
+    parent
+        .children()
+        .filter(|child| child.has_tag_name("outline"))
+        .map(|outline| {
+            Ok(Node {
+                name: get_name(outline, template, default)?,
+                children: outlines_to_nodes(outline, template, default)?,
+            })
+        })
+        .collect()
+}
+
+// `{name}` is the outline's `text` (or `title`, which some tools use instead);
+// any other placeholder is an attribute
+fn get_name(
+    outline: XmlNode,
+    template: &Template,
+    default: &Option<String>,
+) -> Result<String, Error> {
+    template.fill_with_default(
+        |placeholder_name| {
+            if placeholder_name == "name" {
+                outline
+                    .attribute("text")
+                    .or_else(|| outline.attribute("title"))
+                    .map(str::to_string)
+            } else {
+                outline.attribute(placeholder_name).map(str::to_string)
+            }
+        },
+        default,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parens;
+    use super::*;
+
+    const FEEDS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<opml version="2.0">
+  <head><title>My Feeds</title></head>
+  <body>
+    <outline text="Tech">
+      <outline type="rss" text="Hacker News" xmlUrl="https://news.ycombinator.com/rss"/>
+      <outline type="rss" title="Lobsters" xmlUrl="https://lobste.rs/rss"/>
+    </outline>
+    <outline text="Empty folder"/>
+  </body>
+</opml>
+"#;
+
+    #[test]
+    fn zero_length_opml() {
+        let deserialization_err =
+            deserialize("".to_string(), "{name}".to_string(), None, None).unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn not_opml() {
+        let deserialization_err =
+            deserialize("<html/>".to_string(), "{name}".to_string(), None, None).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("root element must be `<opml>`".to_string())
+        );
+    }
+
+    #[test]
+    fn multiple_roots_without_title() {
+        let opml = r#"<opml><body><outline text="A"/><outline text="B"/></body></opml>"#;
+        let deserialization_err =
+            deserialize(opml.to_string(), "{name}".to_string(), None, None).unwrap_err();
+        assert_eq!(deserialization_err, Error::MultipleRootsError);
+    }
+
+    #[test]
+    fn single_root() {
+        let opml = r#"<opml><body><outline text="A"><outline text="B"/></outline></body></opml>"#;
+        let expected = parens::deserialize("A (B)".to_string()).unwrap();
+        assert_eq!(
+            deserialize(opml.to_string(), "{name}".to_string(), None, None).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn title_as_root() {
+        let expected = parens::deserialize(
+            "My Feeds (Tech (Hacker News, Lobsters), Empty folder)".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            deserialize(FEEDS.to_string(), "{name}".to_string(), None, None).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn attribute_template_with_root_name() {
+        let root_node = deserialize(
+            FEEDS.to_string(),
+            "{name} [{type}]".to_string(),
+            Some("folder".to_string()),
+            Some("feeds.opml".to_string()),
+        )
+        .unwrap();
+        let expected = parens::deserialize(
+            "feeds.opml (Tech [folder] (Hacker News [rss], Lobsters [rss]), Empty folder [folder])"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(root_node, expected);
+    }
+}
@@ -0,0 +1,263 @@
+use super::indent;
+use super::template::Template;
+use super::{Error, Node};
+
+const DEFAULT_TODO_KEYWORDS: &[&str] = &["TODO", "DONE"];
+const TODO_SETTINGS: &[&str] = &["#+todo:", "#+seq_todo:", "#+typ_todo:"];
+
+struct Headline {
+    number: usize,
+    level: usize,
+    todo: Option<String>,
+    priority: Option<String>,
+    title: String,
+    tags: Vec<String>,
+}
+
+pub fn deserialize(
+    serialized: String,
+    template_str: String,
+    default: Option<String>,
+    root_name: Option<String>,
+) -> Result<Node, Error> {
+    let template = Template::new(template_str)?;
+    let (headlines, title) = find_headlines(&serialized);
+    let entries = headlines
+        .iter()
+        .map(|headline| {
+            let name = get_name(headline, &template, &default)?;
+            Ok((headline.number, headline.level, name))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    // Without a single top-level headline, the document itself becomes the root
+    indent::levels_to_node(entries, root_name.or(title))
+}
+
+// Besides `{name}` (the headline's title), templates can use `{todo}`,
+// `{priority}`, and `{tags}` (written as in the file, e.g. `:work:urgent:`)
+fn get_name(
+    headline: &Headline,
+    template: &Template,
+    default: &Option<String>,
+) -> Result<String, Error> {
+    template.fill_with_default(
+        |placeholder_name| match placeholder_name {
+            "name" => Some(headline.title.clone()),
+            "todo" => headline.todo.clone(),
+            "priority" => headline.priority.clone(),
+            "tags" if !headline.tags.is_empty() => Some(format!(":{}:", headline.tags.join(":"))),
+            _ => None,
+        },
+        default,
+    )
+}
+
+// Also returns the document's `#+TITLE:`, if it has one
+fn find_headlines(serialized: &str) -> (Vec<Headline>, Option<String>) {
+    let mut todo_keywords: Vec<String> = Vec::new();
+    let mut title = None;
+    // In-buffer settings apply to the whole file, wherever they are
+    for line in serialized.lines() {
+        let trimmed = line.trim();
+        if let Some(value) = strip_setting(trimmed, "#+title:") {
+            title = Some(value.to_string()).filter(|title| !title.is_empty());
+        } else if let Some(value) = TODO_SETTINGS
+            .iter()
+            .find_map(|setting| strip_setting(trimmed, setting))
+        {
+            todo_keywords.extend(
+                value
+                    .split_whitespace()
+                    .filter(|keyword| *keyword != "|")
+                    // Drops fast access keys, as in `TODO(t)`
+                    .map(|keyword| keyword.split('(').next().unwrap_or_default().to_string()),
+            );
+        }
+    }
+    if todo_keywords.is_empty() {
+        todo_keywords = DEFAULT_TODO_KEYWORDS
+            .iter()
+            .map(|keyword| keyword.to_string())
+            .collect();
+    }
+
+    let mut headlines = Vec::new();
+    // The `#+END_...` line closing the block we're in, like a source block
+    let mut block_end: Option<String> = None;
+    for (i, line) in serialized.lines().enumerate() {
+        let number = i + 1;
+        let trimmed = line.trim();
+        if let Some(end) = &block_end {
+            if trimmed.to_lowercase().starts_with(end.as_str()) {
+                block_end = None;
+            }
+            continue;
+        }
+        if let Some(block_type) = strip_setting(trimmed, "#+begin_") {
+            let block_type = block_type.split_whitespace().next().unwrap_or_default();
+            block_end = Some(format!("#+end_{}", block_type.to_lowercase()));
+        } else if let Some(headline) = parse_headline(number, line, &todo_keywords) {
+            headlines.push(headline);
+        }
+        // Everything else (body text, drawers, planning lines) is ignored
+    }
+    (headlines, title)
+}
+
+// In-buffer settings and block markers are case-insensitive
+fn strip_setting<'a>(line: &'a str, setting: &str) -> Option<&'a str> {
+    if line.len() >= setting.len()
+        && line.is_char_boundary(setting.len())
+        && line[..setting.len()].eq_ignore_ascii_case(setting)
+    {
+        Some(line[setting.len()..].trim())
+    } else {
+        None
+    }
+}
+
+fn parse_headline(number: usize, line: &str, todo_keywords: &[String]) -> Option<Headline> {
+    let level = line.chars().take_while(|c| *c == '*').count();
+    let rest = &line[level..];
+    if level == 0 || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    let mut rest = rest.trim();
+
+    let mut todo = None;
+    if let Some(keyword) = todo_keywords.iter().find(|keyword| {
+        rest.strip_prefix(keyword.as_str())
+            .is_some_and(|after| after.is_empty() || after.starts_with([' ', '\t']))
+    }) {
+        todo = Some(keyword.clone());
+        rest = rest[keyword.len()..].trim_start();
+    }
+
+    let mut priority = None;
+    if let Some(after) = rest.strip_prefix("[#") {
+        if let Some(end) = after.find(']') {
+            priority = Some(after[..end].to_string());
+            rest = after[end + 1..].trim_start();
+        }
+    }
+
+    let mut tags = Vec::new();
+    let start = rest.rfind([' ', '\t']).map_or(0, |idx| idx + 1);
+    let candidate = &rest[start..];
+    if candidate.len() > 1
+        && candidate.starts_with(':')
+        && candidate.ends_with(':')
+        && candidate[1..candidate.len() - 1]
+            .split(':')
+            .all(|tag| !tag.is_empty() && tag.chars().all(is_tag_char))
+    {
+        tags = candidate[1..candidate.len() - 1]
+            .split(':')
+            .map(str::to_string)
+            .collect();
+        rest = rest[..start].trim_end();
+    }
+
+    Some(Headline {
+        number,
+        level,
+        todo,
+        priority,
+        title: rest.to_string(),
+        tags,
+    })
+}
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '@' | '#' | '%')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parens;
+    use super::*;
+
+    fn deserialize_org(org: &str, template: &str) -> Result<Node, Error> {
+        deserialize(
+            org.to_string(),
+            template.to_string(),
+            Some("-".to_string()),
+            None,
+        )
+    }
+
+    #[test]
+    fn empty() {
+        let org = "Just some text.\n#+BEGIN_SRC org\n* Not a headline\n#+END_SRC\n";
+        let deserialization_err = deserialize_org(org, "{name}").unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn multiple_roots() {
+        let org = "* Inbox\n* Projects\n";
+        let deserialization_err = deserialize_org(org, "{name}").unwrap_err();
+        assert_eq!(deserialization_err, Error::MultipleRootsError);
+    }
+
+    #[test]
+    fn multiple_roots_with_title() {
+        let org = "#+TITLE: Plans\n* Inbox\n* Projects\n** ruut\n";
+        let expected = parens::deserialize("Plans (Inbox, Projects (ruut))".to_string()).unwrap();
+        assert_eq!(deserialize_org(org, "{name}").unwrap(), expected);
+        assert_eq!(
+            deserialize(
+                org.to_string(),
+                "{name}".to_string(),
+                None,
+                Some("plans.org".to_string())
+            )
+            .unwrap()
+            .name,
+            "plans.org".to_string()
+        );
+    }
+
+    #[test]
+    fn good_org() {
+        let org = r#"
+* Parent
+Some body text.
+:PROPERTIES:
+:ID: 1234
+:END:
+** TODO Child 1 :work:
+** Child 2
+**** Grandchild 1
+#+begin_src python
+* not a headline
+#+end_src
+*** Grandchild 2
+** DONE [#A] Child 3
+SCHEDULED: <2024-01-01 Mon>
+*** Grandchild 3 :a:b:
+*bold* text, not a headline
+"#;
+        let expected = parens::deserialize(
+            "Parent (Child 1, Child 2 (Grandchild 1, Grandchild 2), Child 3 (Grandchild 3))"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(deserialize_org(org, "{name}").unwrap(), expected);
+    }
+
+    #[test]
+    fn keyword_template() {
+        let org = "#+TODO: NEXT(n) WAITING | CANCELLED\n* NEXT [#B] Ship it :work:urgent:\n** TODO Not a keyword here\n** WAITING Review 12:30\n";
+        assert_eq!(
+            deserialize_org(org, "{todo} {name} {priority} {tags}").unwrap(),
+            Node {
+                name: "NEXT Ship it B :work:urgent:".to_string(),
+                children: vec![
+                    Node::new("- TODO Not a keyword here - -".to_string()),
+                    Node::new("WAITING Review 12:30 - -".to_string()),
+                ]
+            }
+        );
+    }
+}
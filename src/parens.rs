@@ -1,4 +1,5 @@
 mod parser;
+mod serializer;
 mod tokenizer;
 use super::{Error, Node};
 
@@ -6,3 +7,30 @@ pub fn deserialize(serialized: String) -> Result<Node, Error> {
     let tokens = tokenizer::tokenize(&serialized);
     parser::parse(tokens)
 }
+
+pub fn serialize(root: &Node) -> String {
+    serializer::serialize(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let serialized = r#"Parent ("Child, 1", Child "2" ("(Grandchild)", "\"Grandchild\""), "")"#;
+        let root_node = deserialize(serialized.to_string()).unwrap();
+        assert_eq!(
+            root_node.children[1].children[1].name,
+            "\"Grandchild\"".to_string()
+        );
+        assert_eq!(serialize(&root_node), serialized);
+    }
+
+    #[test]
+    fn single_node_from_stdin() {
+        let root_node = deserialize("solo\n".to_string()).unwrap();
+        assert_eq!(root_node, Node::new("solo".to_string()));
+        assert_eq!(serialize(&root_node), "solo");
+    }
+}
@@ -0,0 +1,81 @@
+use super::super::Node;
+use super::tokenizer::{COMMA, ESCAPE, PAREN_CLOSE, PAREN_OPEN, QUOTE};
+
+pub fn serialize(node: &Node) -> String {
+    let mut serialized = quote_if_needed(&node.name);
+    if !node.children.is_empty() {
+        let children: Vec<String> = node.children.iter().map(serialize).collect();
+        serialized.push_str(&format!(
+            " {}{}{}",
+            PAREN_OPEN,
+            children.join(", "),
+            PAREN_CLOSE
+        ));
+    }
+    serialized
+}
+
+// Names are only quoted when the tokenizer would otherwise split them up, trim
+// them, or mistake them for quoted names
+fn quote_if_needed(name: &str) -> String {
+    let needs_quotes = name.trim().is_empty()
+        || name.trim() != name
+        || name.starts_with(QUOTE)
+        || name.contains([PAREN_OPEN, PAREN_CLOSE, COMMA]);
+    if !needs_quotes {
+        return name.to_string();
+    }
+    let mut quoted = QUOTE.to_string();
+    for c in name.chars() {
+        if c == QUOTE || c == ESCAPE {
+            quoted.push(ESCAPE);
+        }
+        quoted.push(c);
+    }
+    quoted.push(QUOTE);
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaf() {
+        assert_eq!(
+            serialize(&Node::new("cool beans".to_string())),
+            "cool beans"
+        );
+    }
+
+    #[test]
+    fn nested() {
+        let root_node = Node {
+            name: "papa".to_string(),
+            children: vec![
+                Node {
+                    name: "bebe".to_string(),
+                    children: vec![Node::new("gege".to_string())],
+                },
+                Node::new("fefe".to_string()),
+            ],
+        };
+        assert_eq!(serialize(&root_node), "papa (bebe (gege), fefe)");
+    }
+
+    #[test]
+    fn quoted_names() {
+        let root_node = Node {
+            name: "cool, (beans)".to_string(),
+            children: vec![
+                Node::new(r#""better" \beans"#.to_string()),
+                Node::new(" ".to_string()),
+                Node::new(r#"cool "beans""#.to_string()),
+            ],
+        };
+        assert_eq!(
+            serialize(&root_node),
+            r#""cool, (beans)" ("\"better\" \\beans", " ", cool "beans")"#
+        );
+    }
+}
@@ -1,6 +1,8 @@
-const PAREN_OPEN: char = '(';
-const PAREN_CLOSE: char = ')';
-const COMMA: char = ',';
+pub const PAREN_OPEN: char = '(';
+pub const PAREN_CLOSE: char = ')';
+pub const COMMA: char = ',';
+pub const QUOTE: char = '"';
+pub const ESCAPE: char = '\\';
 
 #[derive(Debug, PartialEq)]
 pub enum Token {
@@ -11,10 +13,22 @@ pub enum Token {
 }
 
 pub fn tokenize(serialized: &str) -> Vec<Token> {
+    let chars: Vec<char> = serialized.chars().collect();
     let mut cur_name = String::from("");
     let mut tokens = Vec::new();
 
-    for s in serialized.chars() {
+    let mut i = 0;
+    while i < chars.len() {
+        let s = chars[i];
+        i += 1;
+        if s == QUOTE && cur_name.trim().is_empty() {
+            if let Some((quoted, end)) = read_quoted(&chars, i) {
+                tokens.push(Token::Name(quoted));
+                cur_name = String::from("");
+                i = end;
+                continue;
+            }
+        }
         if let PAREN_OPEN | PAREN_CLOSE | COMMA = s {
             if cur_name.trim().is_empty() {
                 // Previous name token is just whitespace
@@ -41,11 +55,40 @@ pub fn tokenize(serialized: &str) -> Vec<Token> {
         }
     }
     if !cur_name.trim().is_empty() {
-        tokens.push(Token::Name(cur_name));
+        tokens.push(Token::Name(cur_name.trim().to_string()));
     }
     tokens
 }
 
+// Reads a quoted name starting just after its opening `"`, in which `\`
+// escapes the next character. A name only counts as quoted if the quotes
+// surround all of it, so names that merely contain quotes (like `Child "3"`)
+// keep working; otherwise this returns `None` and the quote is just part of
+// the name.
+fn read_quoted(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let mut quoted = String::new();
+    let mut i = start;
+    loop {
+        match chars.get(i)? {
+            &QUOTE => break,
+            &ESCAPE => {
+                i += 1;
+                quoted.push(*chars.get(i)?);
+            }
+            c => quoted.push(*c),
+        }
+        i += 1;
+    }
+    i += 1;
+    while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+        i += 1;
+    }
+    match chars.get(i) {
+        None | Some(&PAREN_OPEN) | Some(&PAREN_CLOSE) | Some(&COMMA) => Some((quoted, i)),
+        Some(_) => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,6 +138,11 @@ mod tests {
         );
     }
 
+    #[test]
+    fn trailing_newline() {
+        assert_eq!(tokenize(" solo\n"), vec![Token::Name("solo".to_string())]);
+    }
+
     #[test]
     fn invalid_combinations() {
         assert_eq!(
@@ -112,4 +160,35 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn quoted_names() {
+        assert_eq!(
+            tokenize(r#""cool, (beans)" ("\"better\" beans\\","")"#),
+            vec![
+                Token::Name("cool, (beans)".to_string()),
+                Token::ParenOpen,
+                Token::Name("\"better\" beans\\".to_string()),
+                Token::Comma,
+                Token::Name("".to_string()),
+                Token::ParenClose
+            ]
+        );
+    }
+
+    #[test]
+    fn partially_quoted_names() {
+        assert_eq!(
+            tokenize(r#"("cool" beans, cool "beans", "cool beans)"#),
+            vec![
+                Token::ParenOpen,
+                Token::Name(r#""cool" beans"#.to_string()),
+                Token::Comma,
+                Token::Name(r#"cool "beans""#.to_string()),
+                Token::Comma,
+                Token::Name(r#""cool beans"#.to_string()),
+                Token::ParenClose
+            ]
+        );
+    }
 }
@@ -0,0 +1,135 @@
+use super::{Error, Node};
+
+const SEPARATOR: char = '/';
+const DEFAULT_ROOT_NAME: &str = ".";
+
+pub fn deserialize(serialized: String, root_name: Option<String>) -> Result<Node, Error> {
+    paths_to_node(serialized.lines().map(str::trim), root_name)
+}
+
+pub(crate) fn paths_to_node<'a>(
+    paths: impl Iterator<Item = &'a str>,
+    root_name: Option<String>,
+) -> Result<Node, Error> {
+    let mut top_level: Vec<Node> = Vec::new();
+    for path in paths {
+        insert_path(&mut top_level, path);
+    }
+    if top_level.len() == 1 {
+        Ok(top_level.pop().unwrap())
+    } else if top_level.is_empty() {
+        Err(Error::EmptyInputError)
+    } else {
+        Ok(Node {
+            name: root_name.unwrap_or_else(|| DEFAULT_ROOT_NAME.to_string()),
+            children: top_level,
+        })
+    }
+}
+
+// Adds a node for each component of the path that isn't already in the tree
+pub(crate) fn insert_path(top_level: &mut Vec<Node>, path: &str) {
+    let mut siblings = top_level;
+    for component in components(path) {
+        siblings = &mut find_or_insert(siblings, component).children;
+    }
+}
+
+// Splits a path into the names of the nodes along it. A trailing `/` (an empty
+// directory) needs no special handling since every component becomes a node
+// regardless of whether it has children.
+pub(crate) fn components(path: &str) -> Vec<&str> {
+    let mut components = Vec::new();
+    if path.starts_with(SEPARATOR) {
+        components.push("/");
+    }
+    components.extend(
+        path.split(SEPARATOR)
+            .filter(|component| !component.is_empty() && *component != "."),
+    );
+    components
+}
+
+fn find_or_insert<'a>(siblings: &'a mut Vec<Node>, name: &str) -> &'a mut Node {
+    match siblings.iter().position(|sibling| sibling.name == name) {
+        Some(idx) => &mut siblings[idx],
+        None => {
+            siblings.push(Node::new(name.to_string()));
+            siblings.last_mut().unwrap()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parens;
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let deserialization_err = deserialize("\n.\n./\n".to_string(), None).unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn single_root() {
+        let paths = r#"
+            src/lib.rs
+            src/parens/parser.rs
+            src/parens/tokenizer.rs
+            src/parens/parser.rs
+            src/empty/
+        "#;
+        let expected = parens::deserialize(
+            "src (lib.rs, parens (parser.rs, tokenizer.rs), empty)".to_string(),
+        )
+        .unwrap();
+        assert_eq!(deserialize(paths.to_string(), None).unwrap(), expected);
+    }
+
+    #[test]
+    fn multiple_top_level_entries() {
+        let paths = r#"
+            ./Cargo.toml
+            ./src/main.rs
+            ./src//lib.rs
+            src/./parens/./parser.rs
+        "#;
+        let expected = parens::deserialize(
+            ". (Cargo.toml, src (main.rs, lib.rs, parens (parser.rs)))".to_string(),
+        )
+        .unwrap();
+        assert_eq!(deserialize(paths.to_string(), None).unwrap(), expected);
+    }
+
+    #[test]
+    fn multiple_top_level_entries_with_root_name() {
+        let paths = "Cargo.toml\nsrc/main.rs\n";
+        let expected = parens::deserialize("ruut (Cargo.toml, src (main.rs))".to_string()).unwrap();
+        assert_eq!(
+            deserialize(paths.to_string(), Some("ruut".to_string())).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn absolute_paths() {
+        let paths = "/usr/bin/env\n/usr/lib/\n";
+        assert_eq!(
+            deserialize(paths.to_string(), None).unwrap(),
+            Node {
+                name: "/".to_string(),
+                children: vec![Node {
+                    name: "usr".to_string(),
+                    children: vec![
+                        Node {
+                            name: "bin".to_string(),
+                            children: vec![Node::new("env".to_string())]
+                        },
+                        Node::new("lib".to_string()),
+                    ]
+                }]
+            }
+        );
+    }
+}
@@ -0,0 +1,40 @@
+mod parser;
+mod tokenizer;
+use super::{Error, Node};
+
+pub fn deserialize(serialized: String) -> Result<Node, Error> {
+    let tokens = tokenizer::tokenize(&serialized)?;
+    parser::parse(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parens;
+    use super::*;
+
+    #[test]
+    fn good_sexp() {
+        let sexp = r#"
+; The whole tree
+(Parent
+  (Child1)
+  ("Child 2" ; quoted, since it has a space
+    Grandchild1
+    (Grandchild2))
+  "Child \"3\"")
+"#;
+        let expected = parens::deserialize(
+            r#"Parent (Child1, Child 2 (Grandchild1, Grandchild2), Child "3")"#.to_string(),
+        )
+        .unwrap();
+        assert_eq!(deserialize(sexp.to_string()).unwrap(), expected);
+    }
+
+    #[test]
+    fn single_atom() {
+        assert_eq!(
+            deserialize("lonely".to_string()).unwrap(),
+            Node::new("lonely".to_string())
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use super::super::{Error, Node};
+use super::tokenizer::Token;
+use std::iter::Peekable;
+use std::vec;
+
+pub fn parse(tokens: Vec<Token>) -> Result<Node, Error> {
+    if tokens.is_empty() {
+        return Err(Error::EmptyInputError);
+    }
+    let token_iter = &mut tokens.into_iter().peekable();
+    let root = parse_expr(token_iter)?;
+    match token_iter.next() {
+        None => Ok(root),
+        Some(Token::ParenClose) => Err(unmatched_close()),
+        Some(_) => Err(Error::MultipleRootsError),
+    }
+}
+
+// Parses either a bare atom (a leaf) or a list whose first atom names the node
+// and whose remaining elements are its children
+fn parse_expr(token_iter: &mut Peekable<vec::IntoIter<Token>>) -> Result<Node, Error> {
+    match token_iter.next() {
+        Some(Token::Atom(name)) => Ok(Node::new(name)),
+        Some(Token::ParenOpen) => {
+            let name = match token_iter.next() {
+                Some(Token::Atom(name)) => name,
+                Some(Token::ParenOpen) => {
+                    return Err(Error::FormatSpecificError(
+                        "list must start with a name, not another list".to_string(),
+                    ));
+                }
+                Some(Token::ParenClose) => {
+                    return Err(Error::FormatSpecificError(
+                        "empty list `()` has no name".to_string(),
+                    ));
+                }
+                None => return Err(missing_close()),
+            };
+            let mut children = Vec::new();
+            loop {
+                match token_iter.peek() {
+                    Some(Token::ParenClose) => {
+                        token_iter.next();
+                        break;
+                    }
+                    Some(_) => children.push(parse_expr(token_iter)?),
+                    None => return Err(missing_close()),
+                }
+            }
+            Ok(Node { name, children })
+        }
+        Some(Token::ParenClose) => Err(unmatched_close()),
+        None => Err(missing_close()),
+    }
+}
+
+fn missing_close() -> Error {
+    Error::FormatSpecificError("list missing closing `)`".to_string())
+}
+
+fn unmatched_close() -> Error {
+    Error::FormatSpecificError("unmatched `)`".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atom(name: &str) -> Token {
+        Token::Atom(name.to_string())
+    }
+
+    #[test]
+    fn empty() {
+        let parser_error = parse(Vec::<Token>::new()).unwrap_err();
+        assert_eq!(parser_error, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn multiple_roots() {
+        let tokens = vec![
+            Token::ParenOpen,
+            atom("papa"),
+            Token::ParenClose,
+            atom("popo"),
+        ];
+        let parser_error = parse(tokens).unwrap_err();
+        assert_eq!(parser_error, Error::MultipleRootsError);
+    }
+
+    #[test]
+    fn unbalanced() {
+        let missing = vec![
+            Token::ParenOpen,
+            atom("papa"),
+            Token::ParenOpen,
+            atom("bebe"),
+        ];
+        assert_eq!(parse(missing).unwrap_err(), missing_close());
+        let extra = vec![
+            Token::ParenOpen,
+            atom("papa"),
+            Token::ParenClose,
+            Token::ParenClose,
+        ];
+        assert_eq!(parse(extra).unwrap_err(), unmatched_close());
+    }
+
+    #[test]
+    fn unnamed_lists() {
+        let empty_list = vec![Token::ParenOpen, Token::ParenClose];
+        assert_eq!(
+            parse(empty_list).unwrap_err(),
+            Error::FormatSpecificError("empty list `()` has no name".to_string())
+        );
+        let nested_first = vec![
+            Token::ParenOpen,
+            Token::ParenOpen,
+            atom("papa"),
+            Token::ParenClose,
+            Token::ParenClose,
+        ];
+        assert_eq!(
+            parse(nested_first).unwrap_err(),
+            Error::FormatSpecificError("list must start with a name, not another list".to_string())
+        );
+    }
+
+    #[test]
+    fn good_token_sequence() {
+        let good_sequence = vec![
+            Token::ParenOpen,
+            atom("papa"),
+            Token::ParenOpen,
+            atom("bebe"),
+            atom("gege"),
+            Token::ParenClose,
+            atom("fefe"),
+            Token::ParenClose,
+        ];
+        let root_node = parse(good_sequence).unwrap();
+        assert_eq!(
+            root_node,
+            Node {
+                name: "papa".to_string(),
+                children: vec![
+                    Node {
+                        name: "bebe".to_string(),
+                        children: vec![Node::new("gege".to_string())]
+                    },
+                    Node::new("fefe".to_string())
+                ]
+            }
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use super::super::Error;
+use std::iter::Peekable;
+use std::str::Chars;
+
+const PAREN_OPEN: char = '(';
+const PAREN_CLOSE: char = ')';
+const QUOTE: char = '"';
+const COMMENT: char = ';';
+
+#[derive(Debug, PartialEq)]
+pub enum Token {
+    ParenOpen,
+    ParenClose,
+    // Bare and quoted atoms alike
+    Atom(String),
+}
+
+pub fn tokenize(serialized: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    let mut chars = serialized.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            COMMENT => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            PAREN_OPEN => tokens.push(Token::ParenOpen),
+            PAREN_CLOSE => tokens.push(Token::ParenClose),
+            QUOTE => tokens.push(Token::Atom(read_quoted(&mut chars)?)),
+            c => {
+                let mut atom = c.to_string();
+                while let Some(next) = chars.peek() {
+                    if is_delimiter(*next) {
+                        break;
+                    }
+                    atom.push(*next);
+                    chars.next();
+                }
+                tokens.push(Token::Atom(atom));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || matches!(c, PAREN_OPEN | PAREN_CLOSE | QUOTE | COMMENT)
+}
+
+// Reads the rest of a quoted string, where a backslash escapes the next
+// character (with `\n` and `\t` standing for a newline and a tab)
+fn read_quoted(chars: &mut Peekable<Chars>) -> Result<String, Error> {
+    let mut quoted = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            QUOTE => return Ok(quoted),
+            '\\' => match chars.next() {
+                Some('n') => quoted.push('\n'),
+                Some('t') => quoted.push('\t'),
+                Some(escaped) => quoted.push(escaped),
+                None => break,
+            },
+            c => quoted.push(c),
+        }
+    }
+    Err(Error::FormatSpecificError(
+        "string missing closing `\"`".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_string() {
+        assert_eq!(tokenize(" ; nothing here").unwrap(), Vec::<Token>::new());
+    }
+
+    #[test]
+    fn atoms_and_parens() {
+        assert_eq!(
+            tokenize("(Parent(Child1) Child-2)").unwrap(),
+            vec![
+                Token::ParenOpen,
+                Token::Atom("Parent".to_string()),
+                Token::ParenOpen,
+                Token::Atom("Child1".to_string()),
+                Token::ParenClose,
+                Token::Atom("Child-2".to_string()),
+                Token::ParenClose,
+            ]
+        );
+    }
+
+    #[test]
+    fn quoted_with_escapes() {
+        assert_eq!(
+            tokenize(r#""say \"hi\"\\\n""#).unwrap(),
+            vec![Token::Atom("say \"hi\"\\\n".to_string())]
+        );
+    }
+
+    #[test]
+    fn unterminated_string() {
+        assert_eq!(
+            tokenize(r#"(Parent "Child)"#).unwrap_err(),
+            Error::FormatSpecificError("string missing closing `\"`".to_string())
+        );
+    }
+}
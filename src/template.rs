@@ -83,6 +83,21 @@ impl Template {
                 .concat(),
         )
     }
+
+    // Fills the template using `lookup`, falling back to `default` for any
+    // placeholder it has no value for
+    pub fn fill_with_default<F>(&self, lookup: F, default: &Option<String>) -> Result<String, Error>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        self.fill(|placeholder_name| {
+            lookup(placeholder_name)
+                .or_else(|| default.clone())
+                .ok_or(Error::FormatSpecificError(
+                    "missing template value".to_string(),
+                ))
+        })
+    }
 }
 
 #[cfg(test)]
@@ -146,4 +161,25 @@ mod tests {
             .unwrap();
         assert_eq!(filled_template, "name-123".to_string())
     }
+    #[test]
+    fn fill_with_default_missing_value() {
+        let template = Template::new("{name} ({id})".to_string()).unwrap();
+        let lookup = |placeholder_name: &str| {
+            if placeholder_name == "name" {
+                Some("Billingsby".to_string())
+            } else {
+                None
+            }
+        };
+        assert_eq!(
+            template.fill_with_default(lookup, &Some("?".to_string())),
+            Ok("Billingsby (?)".to_string())
+        );
+        assert_eq!(
+            template.fill_with_default(lookup, &None),
+            Err(Error::FormatSpecificError(
+                "missing template value".to_string()
+            ))
+        );
+    }
 }
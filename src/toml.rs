@@ -0,0 +1,146 @@
+use super::{Error, Node};
+use toml::Table;
+use toml::Value as TomlValue;
+
+const DEFAULT_ROOT_NAME: &str = ".";
+
+pub fn deserialize(serialized: String, root_name: Option<String>) -> Result<Node, Error> {
+    if serialized.trim().is_empty() {
+        return Err(Error::EmptyInputError);
+    }
+    let table: Table = serialized.parse()?;
+    if table.is_empty() {
+        return Err(Error::EmptyInputError);
+    }
+    // TOML documents are an unnamed table, so the root has to come from elsewhere
+    let name = root_name.unwrap_or_else(|| DEFAULT_ROOT_NAME.to_string());
+    Ok(Node {
+        name,
+        children: table_to_nodes(&table),
+    })
+}
+
+fn table_to_nodes(table: &Table) -> Vec<Node> {
+    table
+        .iter()
+        .flat_map(|(key, value)| toml_value_to_nodes(key, value))
+        .collect()
+}
+
+fn toml_value_to_nodes(key: &str, value: &TomlValue) -> Vec<Node> {
+    match value {
+        TomlValue::Table(table) => vec![Node {
+            name: key.to_string(),
+            children: table_to_nodes(table),
+        }],
+        // Arrays of tables (`[[key]]`) repeat the key once per table
+        TomlValue::Array(values)
+            if !values.is_empty() && values.iter().all(TomlValue::is_table) =>
+        {
+            values
+                .iter()
+                .flat_map(|value| toml_value_to_nodes(key, value))
+                .collect()
+        }
+        _ => vec![Node::new(key.to_string())],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_toml() {
+        let toml = r#"
+            [package
+            name = "ruut"
+        "#;
+        let deserialization_err = deserialize(toml.to_string(), None).unwrap_err();
+        let is_format_error = matches!(deserialization_err, Error::FormatSpecificError(_));
+        assert!(is_format_error);
+    }
+
+    #[test]
+    fn zero_length_toml() {
+        let toml = r#""#;
+        let deserialization_err = deserialize(toml.to_string(), None).unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn comment_only_toml() {
+        let toml = r#"
+            # Nothing to see here
+        "#;
+        let deserialization_err = deserialize(toml.to_string(), None).unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn good_toml() {
+        let toml = r#"
+            [package]
+            name = "ruut"
+            authors = ["Hazel Bachrach"]
+
+            [dependencies]
+            toml = { version = "0.8", features = ["preserve_order"] }
+
+            [[bin]]
+            name = "ruut"
+
+            [[bin]]
+            path = "src/other.rs"
+        "#;
+        let root_node = deserialize(toml.to_string(), Some("Cargo.toml".to_string())).unwrap();
+        assert_eq!(
+            root_node,
+            Node {
+                name: "Cargo.toml".to_string(),
+                children: vec![
+                    Node {
+                        name: "package".to_string(),
+                        children: vec![
+                            Node::new("name".to_string()),
+                            Node::new("authors".to_string()),
+                        ]
+                    },
+                    Node {
+                        name: "dependencies".to_string(),
+                        children: vec![Node {
+                            name: "toml".to_string(),
+                            children: vec![
+                                Node::new("version".to_string()),
+                                Node::new("features".to_string()),
+                            ]
+                        }]
+                    },
+                    Node {
+                        name: "bin".to_string(),
+                        children: vec![Node::new("name".to_string())]
+                    },
+                    Node {
+                        name: "bin".to_string(),
+                        children: vec![Node::new("path".to_string())]
+                    },
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn default_root_name_toml() {
+        let toml = r#"
+            title = "hello"
+        "#;
+        let root_node = deserialize(toml.to_string(), None).unwrap();
+        assert_eq!(
+            root_node,
+            Node {
+                name: ".".to_string(),
+                children: vec![Node::new("title".to_string())]
+            }
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use super::indent::{self, Line};
+use super::{Error, Node};
+
+// Glyphs that introduce a node, as emitted by `render_as_tree` and by
+// `tree --charset ascii`
+pub(crate) const BRANCHES: [&str; 4] = ["├── ", "└── ", "|-- ", "`-- "];
+// Glyphs that only carry an ancestor's branch down to a deeper node
+const CONTINUATIONS: [&str; 3] = ["│   ", "|   ", "    "];
+
+pub fn deserialize(serialized: String) -> Result<Node, Error> {
+    let raw_lines: Vec<&str> = serialized.lines().collect();
+    // A root with an empty name renders as a blank line, which is only told
+    // apart from padding by coming right before the first branch
+    let blank_root_idx = raw_lines
+        .iter()
+        .position(|raw_line| measure_line(raw_line).is_some_and(|(depth, _)| depth > 0))
+        .and_then(|branch_idx| branch_idx.checked_sub(1))
+        .filter(|root_idx| {
+            raw_lines[..=*root_idx]
+                .iter()
+                .all(|raw_line| raw_line.trim().is_empty())
+        });
+    let mut lines = Vec::new();
+    for (i, raw_line) in raw_lines.into_iter().enumerate() {
+        let number = i + 1;
+        if Some(i) == blank_root_idx {
+            lines.push(Line {
+                number,
+                depth: 0,
+                name: raw_line.to_string(),
+            });
+            continue;
+        }
+        if raw_line.trim().is_empty() || (!lines.is_empty() && is_summary(raw_line)) {
+            continue;
+        }
+        let (depth, name) = measure_line(raw_line).ok_or_else(|| {
+            Error::FormatSpecificError(format!(
+                "line {}: expected a branch (e.g. `├── ` or `|-- `) before the name",
+                number
+            ))
+        })?;
+        lines.push(Line {
+            number,
+            depth,
+            name: name.to_string(),
+        });
+    }
+    indent::lines_to_node(lines)
+}
+
+fn measure_line(line: &str) -> Option<(usize, &str)> {
+    let mut rest = line;
+    let mut depth = 0;
+    loop {
+        if let Some(name) = BRANCHES.iter().find_map(|glyph| strip_glyph(rest, glyph)) {
+            return Some((depth + 1, name));
+        }
+        match CONTINUATIONS
+            .iter()
+            .find_map(|glyph| strip_glyph(rest, glyph))
+        {
+            Some(after_continuation) => {
+                rest = after_continuation;
+                depth += 1;
+            }
+            // Anything without a glyph in front of it is the root
+            None if depth == 0 => return Some((0, line)),
+            None => return None,
+        }
+    }
+}
+
+// `tree(1)` pads its glyphs with non-breaking spaces, so those are treated the
+// same as regular spaces
+fn strip_glyph<'a>(line: &'a str, glyph: &str) -> Option<&'a str> {
+    let mut line_chars = line.char_indices();
+    for glyph_char in glyph.chars() {
+        match line_chars.next() {
+            Some((_, c)) if c == glyph_char || (glyph_char == ' ' && c == '\u{a0}') => {}
+            _ => return None,
+        }
+    }
+    Some(line_chars.as_str())
+}
+
+// `tree(1)` ends its output with e.g. `3 directories, 5 files`
+fn is_summary(line: &str) -> bool {
+    let parts = line.trim().split(", ").collect::<Vec<_>>();
+    !parts.is_empty()
+        && parts.len() <= 2
+        && parts.iter().all(|part| {
+            let mut words = part.split(' ');
+            let count = words.next().unwrap_or_default();
+            let noun = words.next().unwrap_or_default();
+            !count.is_empty()
+                && count.chars().all(|c| c.is_ascii_digit())
+                && ["directory", "directories", "file", "files"].contains(&noun)
+                && words.next().is_none()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parens;
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let deserialization_err = deserialize("\n".to_string()).unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn multiple_roots() {
+        let tree = "papa\n└── bebe\npopo\n└── bubu\n";
+        let deserialization_err = deserialize(tree.to_string()).unwrap_err();
+        assert_eq!(deserialization_err, Error::MultipleRootsError);
+    }
+
+    #[test]
+    fn missing_branch() {
+        let tree = "papa\n├── bebe\n│   gege\n";
+        let deserialization_err = deserialize(tree.to_string()).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError(
+                "line 3: expected a branch (e.g. `├── ` or `|-- `) before the name".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let root = parens::deserialize(
+            "Parent (Child 1, Child 2 (Grandchild 1, Grandchild 2), Child 3 (Grandchild 3))"
+                .to_string(),
+        )
+        .unwrap();
+        let rendered = render_as_tree::render(&root).join("\n");
+        assert_eq!(deserialize(rendered).unwrap(), root);
+    }
+
+    #[test]
+    fn round_trip_empty_root() {
+        let root = Node {
+            name: "".to_string(),
+            children: vec![
+                Node {
+                    name: "".to_string(),
+                    children: vec![Node::new("A".to_string()), Node::new("B".to_string())],
+                },
+                Node::new("C".to_string()),
+            ],
+        };
+        let rendered = render_as_tree::render(&root).join("\n");
+        assert_eq!(deserialize(rendered.clone()).unwrap(), root);
+        assert_eq!(deserialize(format!("\n\n{}\n", rendered)).unwrap(), root);
+    }
+
+    #[test]
+    fn ascii_charset() {
+        let tree = r#".
+|-- Child 1
+|-- Child 2
+|   |-- Grandchild 1
+|   `-- Grandchild 2
+`-- Child 3
+    `-- Grandchild 3
+
+4 directories, 3 files
+"#;
+        let expected = parens::deserialize(
+            ". (Child 1, Child 2 (Grandchild 1, Grandchild 2), Child 3 (Grandchild 3))".to_string(),
+        )
+        .unwrap();
+        assert_eq!(deserialize(tree.to_string()).unwrap(), expected);
+    }
+
+    #[test]
+    fn non_breaking_spaces() {
+        let tree = ".\n├──\u{a0}Child 1\n│\u{a0}\u{a0} └── Grandchild 1\n└── Child 2\n";
+        let expected =
+            parens::deserialize(". (Child 1 (Grandchild 1), Child 2)".to_string()).unwrap();
+        assert_eq!(deserialize(tree.to_string()).unwrap(), expected);
+    }
+}
@@ -0,0 +1,224 @@
+use super::template::Template;
+use super::{Error, Node};
+use roxmltree::{Document, ExpandedName, ParsingOptions};
+
+type XmlNode<'a, 'input> = roxmltree::Node<'a, 'input>;
+
+const ATTRIBUTE_PREFIX: char = '@';
+
+pub fn deserialize(
+    serialized: String,
+    template_str: String,
+    default: Option<String>,
+    ignore_text: bool,
+    ignore_comments: bool,
+) -> Result<Node, Error> {
+    if serialized.trim().is_empty() {
+        return Err(Error::EmptyInputError);
+    }
+    let parsing_options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let document = Document::parse_with_options(&serialized, parsing_options)?;
+    let template = Template::new(template_str)?;
+    let settings = Settings {
+        template: &template,
+        default: &default,
+        ignore_text,
+        ignore_comments,
+    };
+    xml_node_to_node(document.root_element(), &settings)?.ok_or(Error::EmptyInputError)
+}
+
+struct Settings<'a> {
+    template: &'a Template,
+    default: &'a Option<String>,
+    ignore_text: bool,
+    ignore_comments: bool,
+}
+
+fn xml_node_to_node(xml_node: XmlNode, settings: &Settings) -> Result<Option<Node>, Error> {
+    if xml_node.is_element() {
+        let name = get_name(xml_node, settings)?;
+        let children = xml_node
+            .children()
+            .flat_map(|child| Result::transpose(xml_node_to_node(child, settings)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Some(Node { name, children }))
+    } else if xml_node.is_text() && !settings.ignore_text {
+        Ok(xml_node
+            .text()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(|text| Node::new(text.to_string())))
+    } else if xml_node.is_comment() && !settings.ignore_comments {
+        Ok(xml_node
+            .text()
+            .map(|text| Node::new(format!("<!-- {} -->", text.trim()))))
+    } else {
+        Ok(None)
+    }
+}
+
+// Besides attributes (`{id}` or `{@id}`), templates can use `{tag}` (or
+// `{name}`) for the element's tag name and `{text}` for its own text content
+fn get_name(element: XmlNode, settings: &Settings) -> Result<String, Error> {
+    settings.template.fill_with_default(
+        |placeholder_name| match placeholder_name.strip_prefix(ATTRIBUTE_PREFIX) {
+            Some(attribute_name) => get_attribute(element, attribute_name),
+            None => match placeholder_name {
+                "tag" | "name" => Some(qualified_name(element, element.tag_name())),
+                "text" => get_text(element),
+                attribute_name => get_attribute(element, attribute_name),
+            },
+        },
+        settings.default,
+    )
+}
+
+// Attributes can be referred to either by their local name (`id`) or with the
+// prefix used in the document (`android:id`)
+fn get_attribute(element: XmlNode, attribute_name: &str) -> Option<String> {
+    element
+        .attributes()
+        .find(|attribute| {
+            attribute.name() == attribute_name
+                || qualified_name(
+                    element,
+                    ExpandedName::from((
+                        attribute.namespace().unwrap_or_default(),
+                        attribute.name(),
+                    )),
+                ) == attribute_name
+        })
+        .map(|attribute| attribute.value().to_string())
+}
+
+fn get_text(element: XmlNode) -> Option<String> {
+    let text = element
+        .children()
+        .filter(XmlNode::is_text)
+        .filter_map(|child| child.text())
+        .collect::<String>();
+    let text = text.trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_string())
+    }
+}
+
+fn qualified_name(element: XmlNode, name: ExpandedName) -> String {
+    match name
+        .namespace()
+        .and_then(|namespace| element.lookup_prefix(namespace))
+    {
+        Some(prefix) if !prefix.is_empty() => format!("{}:{}", prefix, name.name()),
+        _ => name.name().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parens;
+    use super::*;
+
+    const LAYOUT: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<LinearLayout xmlns:android="http://schemas.android.com/apk/res/android"
+    android:id="@+id/root">
+    <!-- The header -->
+    <TextView android:id="@+id/title" name="title">Hello</TextView>
+    <Button android:id="@+id/ok" />
+</LinearLayout>
+"#;
+
+    fn deserialize_layout(
+        template: &str,
+        default: Option<String>,
+        ignore_text: bool,
+        ignore_comments: bool,
+    ) -> Result<Node, Error> {
+        deserialize(
+            LAYOUT.to_string(),
+            template.to_string(),
+            default,
+            ignore_text,
+            ignore_comments,
+        )
+    }
+
+    #[test]
+    fn invalid_xml() {
+        let xml = "<root><child></root>";
+        let deserialization_err =
+            deserialize(xml.to_string(), "{name}".to_string(), None, false, false).unwrap_err();
+        let is_format_error = matches!(deserialization_err, Error::FormatSpecificError(_));
+        assert!(is_format_error);
+    }
+
+    #[test]
+    fn zero_length_xml() {
+        let deserialization_err =
+            deserialize("".to_string(), "{name}".to_string(), None, false, false).unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn tag_names_with_text_and_comments() {
+        let expected = parens::deserialize(
+            "LinearLayout (<!-- The header -->, TextView (Hello), Button)".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            deserialize_layout("{name}", None, false, false).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn ignore_text_and_comments() {
+        let expected = parens::deserialize("LinearLayout (TextView, Button)".to_string()).unwrap();
+        assert_eq!(
+            deserialize_layout("{tag}", None, true, true).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn attribute_template() {
+        let root_node = deserialize_layout(
+            "{tag} #{android:id} {@name}",
+            Some("-".to_string()),
+            true,
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            root_node,
+            Node {
+                name: "LinearLayout #@+id/root -".to_string(),
+                children: vec![
+                    Node::new("TextView #@+id/title title".to_string()),
+                    Node::new("Button #@+id/ok -".to_string()),
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn local_attribute_name_and_text_template() {
+        let root_node =
+            deserialize_layout("{id}: {text}", Some("".to_string()), true, true).unwrap();
+        assert_eq!(root_node.children[0].name, "@+id/title: Hello".to_string());
+    }
+
+    #[test]
+    fn missing_attribute_no_default() {
+        let deserialization_err = deserialize_layout("{@name}", None, true, true).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("missing template value".to_string())
+        );
+    }
+}
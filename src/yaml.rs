@@ -0,0 +1,371 @@
+use super::json::node_to_json_value;
+use super::{Error, LeafValue, Node};
+use serde_json::Value as JsonValue;
+use serde_yaml::Value as YamlValue;
+
+pub fn deserialize(serialized: String) -> Result<Node, Error> {
+    if serialized.trim().is_empty() {
+        return Err(Error::EmptyInputError);
+    }
+    let root_value: YamlValue = serde_yaml::from_str(&serialized)?;
+    match root_value {
+        YamlValue::Mapping(map) => {
+            if map.len() > 1 {
+                Err(Error::MultipleRootsError)
+            } else if map.is_empty() {
+                Err(Error::EmptyInputError)
+            } else {
+                let root_entry = map.iter().next().unwrap();
+                Ok(yaml_value_to_node(
+                    key_to_string(root_entry.0),
+                    root_entry.1,
+                ))
+            }
+        }
+        YamlValue::Null => Err(Error::EmptyInputError),
+        _ => Err(Error::FormatSpecificError(
+            "root item must be a mapping".to_string(),
+        )),
+    }
+}
+
+// Scalars that YAML 1.1 (which most config tooling still loads with) reads as
+// booleans, nulls, merge keys (`<<`), or value keys (`=`), even though YAML 1.2
+// reads them as strings
+const YAML_1_1_KEYWORDS: &[&str] = &[
+    "y", "Y", "yes", "Yes", "YES", "n", "N", "no", "No", "NO", "true", "True", "TRUE", "false",
+    "False", "FALSE", "on", "On", "ON", "off", "Off", "OFF", "~", "null", "Null", "NULL", "<<",
+    "=",
+];
+
+// Goes through the same nested-key value as `json` output, so a node with two
+// children of the same name is an error here too
+pub fn serialize(root: &Node, leaf_value: &LeafValue) -> Result<String, Error> {
+    let root_value = node_to_json_value(root, leaf_value)?;
+    let mut lines = Vec::new();
+    push_entry(&root.name, &root_value, 0, &mut lines)?;
+    Ok(lines.join("\n"))
+}
+
+fn push_entry(
+    name: &str,
+    value: &JsonValue,
+    depth: usize,
+    lines: &mut Vec<String>,
+) -> Result<(), Error> {
+    let key = format!("{}{}:", "  ".repeat(depth), yaml_key(name)?);
+    match value {
+        JsonValue::Object(map) if !map.is_empty() => {
+            lines.push(key);
+            for (child_name, child_value) in map {
+                push_entry(child_name, child_value, depth + 1, lines)?;
+            }
+        }
+        JsonValue::Object(_) => lines.push(key + " {}"),
+        _ => lines.push(key + " null"),
+    }
+    Ok(())
+}
+
+// Leaves the quoting of names that YAML would otherwise misread (like `a: b`
+// or `#c`) to `serde_yaml`, which only knows about YAML 1.2, so names that
+// YAML 1.1 misreads are quoted here
+fn yaml_key(name: &str) -> Result<String, Error> {
+    if YAML_1_1_KEYWORDS.contains(&name) || looks_like_timestamp(name) || looks_like_number(name) {
+        return Ok(format!("'{}'", name));
+    }
+    let scalar = serde_yaml::to_string(name)?;
+    let scalar = scalar.trim_end();
+    if scalar.contains('\n') {
+        // A block scalar can't be a key, but a JSON string is also a valid
+        // double-quoted YAML one
+        Ok(JsonValue::String(name.to_string()).to_string())
+    } else {
+        Ok(scalar.to_string())
+    }
+}
+
+// YAML 1.1 numbers can also have `_` separators (`1_000`), be written in base
+// 60 (`1:30`), or be binary (`0b101`); plain numbers are already quoted by
+// `serde_yaml`
+fn looks_like_number(name: &str) -> bool {
+    let unsigned = name.strip_prefix(['+', '-']).unwrap_or(name);
+    let is_made_of = |digits: &str, is_digit: fn(&char) -> bool| {
+        !digits.is_empty() && digits.chars().all(|c| c == '_' || is_digit(&c))
+    };
+    if let Some(digits) = unsigned.strip_prefix("0b") {
+        is_made_of(digits, |c| matches!(c, '0' | '1'))
+    } else if let Some(digits) = unsigned.strip_prefix("0x") {
+        is_made_of(digits, char::is_ascii_hexdigit)
+    } else {
+        unsigned.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+            && unsigned.contains(['_', ':'])
+            && unsigned
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, '_' | ':' | '.'))
+    }
+}
+
+// YAML 1.1 timestamps start with a `yyyy-m-d` date, optionally followed by a
+// time after a `T` or whitespace
+fn looks_like_timestamp(name: &str) -> bool {
+    let is_number = |part: &str, min_len: usize, max_len: usize| {
+        (min_len..=max_len).contains(&part.len()) && part.chars().all(|c| c.is_ascii_digit())
+    };
+    let mut parts = name.splitn(3, '-');
+    let (year, month, rest) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(year), Some(month), Some(rest)) => (year, month, rest),
+        _ => return false,
+    };
+    let day_len = rest.chars().take_while(char::is_ascii_digit).count();
+    let after_day = &rest[day_len..];
+    is_number(year, 4, 4)
+        && is_number(month, 1, 2)
+        && is_number(&rest[..day_len], 1, 2)
+        && (after_day.is_empty()
+            || (after_day.starts_with(['T', 't', ' ', '\t'])
+                && after_day[1..]
+                    .trim_start()
+                    .starts_with(|c: char| c.is_ascii_digit())))
+}
+
+fn yaml_value_to_node(name: String, value: &YamlValue) -> Node {
+    match value {
+        YamlValue::Mapping(map) => Node {
+            name,
+            children: map
+                .iter()
+                .map(|(key, value)| yaml_value_to_node(key_to_string(key), value))
+                .collect(),
+        },
+        YamlValue::Tagged(tagged) => yaml_value_to_node(name, &tagged.value),
+        _ => Node::new(name),
+    }
+}
+
+// Lets the property-based walker in `json_properties` handle YAML documents
+pub(crate) fn yaml_value_to_json_value(value: YamlValue) -> JsonValue {
+    match value {
+        YamlValue::Null => JsonValue::Null,
+        YamlValue::Bool(b) => JsonValue::Bool(b),
+        YamlValue::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                JsonValue::from(i)
+            } else if let Some(u) = n.as_u64() {
+                JsonValue::from(u)
+            } else {
+                n.as_f64().map_or(JsonValue::Null, JsonValue::from)
+            }
+        }
+        YamlValue::String(s) => JsonValue::String(s),
+        YamlValue::Sequence(seq) => {
+            JsonValue::Array(seq.into_iter().map(yaml_value_to_json_value).collect())
+        }
+        YamlValue::Mapping(map) => JsonValue::Object(
+            map.into_iter()
+                .map(|(key, value)| (key_to_string(&key), yaml_value_to_json_value(value)))
+                .collect(),
+        ),
+        YamlValue::Tagged(tagged) => yaml_value_to_json_value(tagged.value),
+    }
+}
+
+// YAML allows any value as a mapping key, so non-string keys are written out
+// the way they appear in the document (e.g. `1`, `true`, `null`)
+fn key_to_string(key: &YamlValue) -> String {
+    match key {
+        YamlValue::String(s) => s.to_string(),
+        YamlValue::Number(n) => n.to_string(),
+        YamlValue::Bool(b) => b.to_string(),
+        YamlValue::Null => "null".to_string(),
+        YamlValue::Tagged(tagged) => key_to_string(&tagged.value),
+        other => serde_yaml::to_string(other)
+            .map(|s| s.trim_end().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_yaml() {
+        let yaml = r#"
+cool:
+  beans: [
+another one: {
+"#;
+        let deserialization_err = deserialize(yaml.to_string()).unwrap_err();
+        let is_format_error = matches!(deserialization_err, Error::FormatSpecificError(_));
+        assert!(is_format_error);
+    }
+
+    #[test]
+    fn zero_length_yaml() {
+        let yaml = r#""#;
+        let deserialization_err = deserialize(yaml.to_string()).unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn empty_mapping_yaml() {
+        let yaml = r#"{}"#;
+        let deserialization_err = deserialize(yaml.to_string()).unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn multiple_roots_yaml() {
+        let yaml = r#"
+cool:
+  beans:
+    man: null
+  wow:
+another one:
+"#;
+        let deserialization_err = deserialize(yaml.to_string()).unwrap_err();
+        assert_eq!(deserialization_err, Error::MultipleRootsError);
+    }
+
+    #[test]
+    fn non_mapping_root_yaml() {
+        let yaml = r#"
+- cool
+- beans
+"#;
+        let deserialization_err = deserialize(yaml.to_string()).unwrap_err();
+        assert_eq!(
+            deserialization_err,
+            Error::FormatSpecificError("root item must be a mapping".to_string())
+        );
+    }
+
+    #[test]
+    fn good_yaml() {
+        let yaml = r#"
+cool:
+  beans:
+    man: ~
+    1: doesn't matter
+  wow: [1, 2, 3]
+"#;
+        let root_node = deserialize(yaml.to_string()).unwrap();
+        assert_eq!(
+            root_node,
+            Node {
+                name: "cool".to_string(),
+                children: vec![
+                    Node {
+                        name: "beans".to_string(),
+                        children: vec![
+                            Node {
+                                name: "man".to_string(),
+                                children: Vec::new()
+                            },
+                            Node {
+                                name: "1".to_string(),
+                                children: Vec::new()
+                            }
+                        ]
+                    },
+                    Node {
+                        name: "wow".to_string(),
+                        children: Vec::new()
+                    }
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        let names = [
+            "plain",
+            "key: value",
+            "# not a comment",
+            "  leading spaces",
+            "true",
+            "null",
+            "123",
+            "- not a list",
+            "'quoted'",
+            "\"double\"",
+            "two\nlines",
+            "",
+            "@at",
+            "{}",
+            "y",
+            "no",
+            "On",
+            "OFF",
+            "NULL",
+            "2024-01-01",
+            "2001-12-14t21:59:43.10-05:00",
+            "2001-12-14 21:59:43.10 -5",
+            "190:20:30.15",
+            "-1_000",
+            "<<",
+            "a name long enough that it would wrap if the emitter folded long plain scalars",
+        ];
+        let root_node = Node {
+            name: "root: node".to_string(),
+            children: names
+                .iter()
+                .map(|name| Node {
+                    name: name.to_string(),
+                    children: vec![Node::new(name.to_string())],
+                })
+                .collect(),
+        };
+        for leaf_value in [LeafValue::Null, LeafValue::EmptyObject] {
+            let yaml = serialize(&root_node, &leaf_value).unwrap();
+            assert_eq!(deserialize(yaml).unwrap(), root_node);
+        }
+    }
+
+    #[test]
+    fn serialize_leaves() {
+        let root_node = Node {
+            name: "Parent".to_string(),
+            children: vec![
+                Node {
+                    name: "Child: 1".to_string(),
+                    children: vec![Node::new("Grandchild".to_string())],
+                },
+                Node::new("Child 2".to_string()),
+            ],
+        };
+        assert_eq!(
+            serialize(&root_node, &LeafValue::Null).unwrap(),
+            "Parent:\n  'Child: 1':\n    Grandchild: null\n  Child 2: null"
+        );
+        assert_eq!(
+            serialize(&root_node, &LeafValue::EmptyObject).unwrap(),
+            "Parent:\n  'Child: 1':\n    Grandchild: {}\n  Child 2: {}"
+        );
+    }
+
+    #[test]
+    fn serialize_yaml_1_1_scalars() {
+        let root_node = Node {
+            name: "y".to_string(),
+            children: vec![
+                Node::new("on".to_string()),
+                Node::new("2024-01-01".to_string()),
+                Node::new("2024-01-01 notes".to_string()),
+                Node::new("yes please".to_string()),
+                Node::new("1:30".to_string()),
+                Node::new("1_000".to_string()),
+                Node::new("0x_1F".to_string()),
+                Node::new("<<".to_string()),
+                Node::new("1:30 pm".to_string()),
+                Node::new("two\nlines".to_string()),
+            ],
+        };
+        assert_eq!(
+            serialize(&root_node, &LeafValue::Null).unwrap(),
+            "'y':\n  'on': null\n  '2024-01-01': null\n  2024-01-01 notes: null\n  yes please: null\n  '1:30': null\n  '1_000': null\n  '0x_1F': null\n  '<<': null\n  1:30 pm: null\n  \"two\\nlines\": null"
+        );
+    }
+}
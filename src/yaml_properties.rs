@@ -0,0 +1,134 @@
+use super::json_properties;
+use super::yaml::yaml_value_to_json_value;
+use super::{Error, Node};
+use serde_yaml::Value as YamlValue;
+
+pub fn deserialize(
+    serialized: String,
+    template_str: String,
+    children_key: String,
+    default: Option<String>,
+) -> Result<Node, Error> {
+    if serialized.trim().is_empty() {
+        return Err(Error::EmptyInputError);
+    }
+    let root_value: YamlValue = serde_yaml::from_str(&serialized)?;
+    if root_value.is_null() {
+        return Err(Error::EmptyInputError);
+    }
+    json_properties::value_to_node(
+        yaml_value_to_json_value(root_value),
+        template_str,
+        children_key,
+        default,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_length_yaml() {
+        let yaml = r#""#;
+        let deserialization_err = deserialize(
+            yaml.to_string(),
+            "{name}".to_string(),
+            "children".to_string(),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(deserialization_err, Error::EmptyInputError);
+    }
+
+    #[test]
+    fn multiple_roots_seq_yaml() {
+        let yaml = r#"
+- name: first root
+- name: second root
+  children:
+    beans:
+      name: me, the bean man
+"#;
+        let deserialization_err = deserialize(
+            yaml.to_string(),
+            "{name}".to_string(),
+            "children".to_string(),
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(deserialization_err, Error::MultipleRootsError);
+    }
+
+    #[test]
+    fn good_yaml() {
+        let yaml = r#"
+name: big root boy
+id: 1
+children:
+  - name: me, the bean man
+    id: 2
+  - name: another child of beans
+    id: 3
+    children: ~
+"#;
+        let root_node = deserialize(
+            yaml.to_string(),
+            "{name} ({id})".to_string(),
+            "children".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            root_node,
+            Node {
+                name: "big root boy (1)".to_string(),
+                children: vec![
+                    Node {
+                        name: "me, the bean man (2)".to_string(),
+                        children: Vec::new()
+                    },
+                    Node {
+                        name: "another child of beans (3)".to_string(),
+                        children: Vec::new()
+                    }
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn yaml_with_missing_prop_with_default() {
+        let yaml = r#"
+moniker: big root boy
+progeny:
+  whatever_1:
+    moniker: me, the bean man
+  whatever_2:
+    not_a_moniker: nope
+"#;
+        let root_node = deserialize(
+            yaml.to_string(),
+            "{moniker}".to_string(),
+            "progeny".to_string(),
+            Some("<missing>".to_string()),
+        )
+        .unwrap();
+        assert_eq!(
+            root_node,
+            Node {
+                name: "big root boy".to_string(),
+                children: vec![
+                    Node {
+                        name: "me, the bean man".to_string(),
+                        children: Vec::new()
+                    },
+                    Node {
+                        name: "<missing>".to_string(),
+                        children: Vec::new()
+                    }
+                ]
+            }
+        );
+    }
+}